@@ -11,4 +11,58 @@ pub enum SevenSeasError {
     MaxShipLevelReached,
     CouldNotFindAShipToAttack,
     InvalidTileState,
+    InvalidBoardGenParams,
+    TileIsBlocked,
+    InvalidBoardSize,
+    PlayerTableFull,
+    Unauthorized,
+    NotANeighbourChunk,
+    AccountNeedsMigration,
+    AccountAlreadyMigrated,
+    UnknownAccountLayout,
+    GamePaused,
+    InvalidPauseFlags,
+    InvalidMint,
+    ShipIsDeployed,
+    InvalidShopItem,
+    ShopDailyLimitReached,
+    NothingToClaim,
+    DestinationRequiresFullMove,
+    ProfileNameTooLong,
+    InvalidShipClass,
+    CannonSlotsFull,
+    ActionOnCooldown,
+    NothingToRepair,
+    RepairNotAllowed,
+    InvalidAmmoType,
+    MissingAmmoAccounts,
+    InvalidWreckPercent,
+    InvalidLootItem,
+    TreasuryEmpty,
+    InvalidNpcBehaviour,
+    InvalidGuildId,
+    AlreadyInGuild,
+    GuildFull,
+    NotAGuildMember,
+    AlliedShip,
+    InvalidBounty,
+    BountyExpired,
+    BountyNotRefundable,
+    InvalidTournament,
+    TournamentFull,
+    AlreadyInTournament,
+    TournamentClosed,
+    TournamentNotOver,
+    TournamentFinalized,
+    InvalidPrizeWinners,
+    RoyaleNotAllowed,
+    RoyaleNotOpen,
+    RoyaleNotRunning,
+    RoyaleInProgress,
+    RoyaleNotOver,
+    MissingTargetProfile,
+    WrongBoard,
+    MissingTeammateAccount,
+    DuplicateAccount,
+    MissingShipAccount,
 }
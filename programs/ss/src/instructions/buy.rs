@@ -0,0 +1,167 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, GameConfig, MintRegistry, Shop, ShopPurchaseRecord, GAME_CONFIG_VERSION,
+    MINT_REGISTRY_VERSION, PAUSE_BUY, SECONDS_PER_DAY, SHOP_ITEM_CANNON, SHOP_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, MintTo, Token, TokenAccount, Transfer},
+};
+
+/// 在商店购买大炮或朗姆酒
+///
+/// # 参数
+/// * `item` - 商品,见 SHOP_ITEM_* 常量
+/// * `amount` - 购买数量,按整数代币计
+///
+/// # 功能说明
+/// 1. 检查每个钱包的每日限购
+/// 2. 把金币从玩家代币账户转入金币金库
+/// 3. 通过铸币权限PDA给玩家铸造商品代币
+pub fn buy(ctx: Context<Buy>, item: u8, amount: u64) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_BUY)?;
+    let shop = &ctx.accounts.shop;
+    let registry = &ctx.accounts.mint_registry;
+
+    // 1. 检查商品和铸币账户
+    let price = shop.price(item).ok_or(SevenSeasError::InvalidShopItem)?;
+    let item_mint = if item == SHOP_ITEM_CANNON {
+        registry.cannon_mint
+    } else {
+        registry.rum_mint
+    };
+    if ctx.accounts.item_mint.key() != item_mint {
+        return Err(SevenSeasError::InvalidMint.into());
+    }
+
+    // 2. 检查每日限购
+    let today = Clock::get()?.unix_timestamp / SECONDS_PER_DAY;
+    let record = &mut ctx.accounts.purchase_record;
+    record.version = SHOP_VERSION;
+    if !record.record(today, amount, shop.daily_limit) {
+        return Err(SevenSeasError::ShopDailyLimitReached.into());
+    }
+
+    // 3. 支付金币
+    let cost = price
+        .checked_mul(amount)
+        .ok_or(SevenSeasError::InvalidShopItem)?;
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.player_gold_account.to_account_info(),
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        },
+    );
+    anchor_spl::token::transfer(
+        cpi_ctx,
+        to_base_units(cost, ctx.accounts.gold_mint.decimals),
+    )?;
+
+    // 4. 铸造商品代币
+    let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
+    let signer = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.item_mint.to_account_info(),
+            to: ctx.accounts.player_item_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        },
+        signer,
+    );
+    anchor_spl::token::mint_to(
+        cpi_ctx,
+        to_base_units(amount, ctx.accounts.item_mint.decimals),
+    )?;
+
+    msg!("Bought {} of item {} for {} gold", amount, item, cost);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Buy<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 商店账户
+    #[account(
+        seeds = [b"shop"],
+        bump,
+        constraint = shop.version == SHOP_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub shop: Account<'info, Shop>,
+
+    /// 钱包的购买记录,用于每日限购
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"shop_record", signer.key().as_ref()],
+        bump,
+        space = 8 + ShopPurchaseRecord::INIT_SPACE
+    )]
+    pub purchase_record: Account<'info, ShopPurchaseRecord>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 玩家金币代币账户
+    #[account(
+        mut,
+        associated_token::mint = gold_mint,
+        associated_token::authority = signer
+    )]
+    pub player_gold_account: Account<'info, TokenAccount>,
+
+    /// 金币金库
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), gold_mint.key().as_ref()],
+        bump,
+        token::mint = gold_mint
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// 金币铸币账户
+    #[account(address = mint_registry.gold_mint @ SevenSeasError::InvalidMint)]
+    pub gold_mint: Account<'info, Mint>,
+
+    /// 玩家商品代币账户,不存在时创建
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = item_mint,
+        associated_token::authority = signer
+    )]
+    pub player_item_account: Account<'info, TokenAccount>,
+
+    /// 商品铸币账户,在指令中检查与商品是否一致
+    #[account(mut)]
+    pub item_mint: Account<'info, Mint>,
+
+    /// CHECK: 大炮和朗姆酒的铸币权限PDA
+    #[account(
+        seeds = [b"mint_authority".as_ref()],
+        bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,113 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, GameConfig, MintRegistry, PlayerProfile, GAME_CONFIG_VERSION,
+    MINT_REGISTRY_VERSION, PAUSE_CLAIM_LOOT, PLAYER_PROFILE_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, MintTo, Token, TokenAccount},
+};
+
+/// 领取从宝箱和残骸中收集的代币物品
+///
+/// # 参数
+/// * `item` - 物品,见 LOOT_ITEM_* 常量
+///
+/// # 功能说明
+/// 收集宝箱和残骸时物品只记入玩家档案,这里通过铸币权限PDA
+/// 一次性给玩家铸造该物品的全部待领取数量
+pub fn claim_loot(ctx: Context<ClaimLoot>, item: u8) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_CLAIM_LOOT)?;
+    let item_mint = ctx
+        .accounts
+        .mint_registry
+        .loot_mint(item)
+        .ok_or(SevenSeasError::InvalidLootItem)?;
+    if ctx.accounts.item_mint.key() != item_mint {
+        return Err(SevenSeasError::InvalidMint.into());
+    }
+
+    let amount = ctx
+        .accounts
+        .profile
+        .take_loot(item)
+        .ok_or(SevenSeasError::InvalidLootItem)?;
+    if amount == 0 {
+        return Err(SevenSeasError::NothingToClaim.into());
+    }
+
+    let seeds = &[b"mint_authority".as_ref(), &[ctx.bumps.mint_authority]];
+    let signer = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.item_mint.to_account_info(),
+            to: ctx.accounts.player_item_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        },
+        signer,
+    );
+    anchor_spl::token::mint_to(
+        cpi_ctx,
+        to_base_units(amount, ctx.accounts.item_mint.decimals),
+    )?;
+
+    msg!("Claimed {} of item {}", amount, item);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimLoot<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 玩家档案
+    #[account(
+        mut,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 玩家物品代币账户,不存在时创建
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = item_mint,
+        associated_token::authority = player
+    )]
+    pub player_item_account: Account<'info, TokenAccount>,
+
+    /// 物品铸币账户,在指令中检查与物品是否一致
+    #[account(mut)]
+    pub item_mint: Account<'info, Mint>,
+
+    /// CHECK: 物品代币的铸币权限PDA
+    #[account(
+        seeds = [b"mint_authority".as_ref()],
+        bump
+    )]
+    pub mint_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,111 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, GameConfig, MintRegistry, PlayerProfile, GAME_CONFIG_VERSION,
+    MINT_REGISTRY_VERSION, PAUSE_CLAIM_REWARDS, PLAYER_PROFILE_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount, Transfer},
+};
+
+/// 领取玩家档案中待领取的金币
+///
+/// # 功能说明
+/// 移动和射击获得的金币只记入玩家档案,
+/// 这里一次性把所有待领取的金币从金币金库转到玩家的代币账户
+pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_CLAIM_REWARDS)?;
+    let amount = ctx.accounts.profile.pending_gold;
+    if amount == 0 {
+        return Err(SevenSeasError::NothingToClaim.into());
+    }
+
+    let seeds = &[
+        b"token_account_owner_pda".as_ref(),
+        &[ctx.bumps.token_account_owner_pda],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.player_token_account.to_account_info(),
+            authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+        },
+        signer,
+    );
+    anchor_spl::token::transfer(
+        cpi_ctx,
+        to_base_units(amount, ctx.accounts.gold_mint.decimals),
+    )?;
+
+    ctx.accounts.profile.pending_gold = 0;
+    msg!("Claimed {} gold", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 玩家档案
+    #[account(
+        mut,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    /// 玩家的金币代币账户,不存在时创建
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = gold_mint,
+        associated_token::authority = player
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    /// 金币金库
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), gold_mint.key().as_ref()],
+        bump,
+        token::mint = gold_mint,
+        token::authority = token_account_owner_pda
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: 金币金库的所有者PDA
+    #[account(
+        seeds = [b"token_account_owner_pda".as_ref()],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
+    /// 金币铸币账户
+    #[account(address = mint_registry.gold_mint @ SevenSeasError::InvalidMint)]
+    pub gold_mint: Account<'info, Mint>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,47 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    ChestVaultAccount, GameBoard, GameConfig, GameDataAccount, CHEST_VAULT_VERSION,
+    GAME_CONFIG_VERSION, PAUSE_CLAIM_ROYALE,
+};
+use anchor_lang::prelude::*;
+
+/// 领取大逃杀奖池
+///
+/// # 功能说明
+/// 棋盘上只剩下一艘玩家的船只时,船主领取奖池和自己托管的击杀奖励,大逃杀随之结束
+pub fn claim_royale(ctx: Context<ClaimRoyale>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_CLAIM_ROYALE)?;
+    GameBoard::load_mut(&ctx.accounts.game_data_account)?.claim_royale(
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.chest_vault.to_account_info(),
+    )?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRoyale<'info> {
+    /// 最后一艘船的船主
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 宝箱金库,奖池托管在这里
+    #[account(
+        mut,
+        seeds = [b"chestVault"],
+        bump,
+        constraint = chest_vault.version == CHEST_VAULT_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub chest_vault: Account<'info, ChestVaultAccount>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,62 @@
+pub use crate::errors::SevenSeasError;
+use crate::{Guild, PlayerProfile, GUILD_VERSION, PLAYER_PROFILE_VERSION};
+use anchor_lang::prelude::*;
+
+/// 创建公会
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `id` - 公会编号,不能为0,同时作为团队模式中的队伍编号
+/// * `name` - 公会名称,不超过 PROFILE_NAME_LEN 字节
+///
+/// # 功能说明
+/// 创建者成为会长和第一个成员,创建者不能已经属于其他公会
+pub fn create_guild(ctx: Context<CreateGuild>, id: u16, name: String) -> Result<()> {
+    if id == 0 {
+        return Err(SevenSeasError::InvalidGuildId.into());
+    }
+    let profile = &mut ctx.accounts.profile;
+    if profile.guild != 0 {
+        return Err(SevenSeasError::AlreadyInGuild.into());
+    }
+
+    let guild = &mut ctx.accounts.guild;
+    guild.version = GUILD_VERSION;
+    guild.id = id;
+    guild.leader = ctx.accounts.leader.key();
+    guild.set_name(&name)?;
+    guild.add_member(ctx.accounts.leader.key())?;
+    profile.guild = id;
+
+    msg!("Guild {} created by {}", id, guild.leader);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u16)]
+pub struct CreateGuild<'info> {
+    /// 会长,支付公会账户的租金
+    #[account(mut)]
+    pub leader: Signer<'info>,
+
+    /// 公会账户,编号已被使用时创建失败
+    #[account(
+        init,
+        payer = leader,
+        seeds = [b"guild", id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Guild::INIT_SPACE
+    )]
+    pub guild: Account<'info, Guild>,
+
+    /// 会长的玩家档案,记录所属的公会
+    #[account(
+        mut,
+        seeds = [b"profile", leader.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,96 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    GameDataAccount, PrizeVault, Tournament, GAME_DATA_VERSION, TOURNAMENT_PRIZE_PLACES,
+    TOURNAMENT_VERSION,
+};
+use anchor_lang::prelude::*;
+
+/// 创建限时锦标赛(仅棋盘管理员)
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `id` - 锦标赛编号,同一个棋盘上不能重复
+/// * `start_slot` - 开始的slot
+/// * `end_slot` - 结束的slot,必须晚于开始的slot
+/// * `entry_fee` - 报名费(lamports)
+/// * `payouts` - 按名次的奖金比例(万分比),之和必须等于 TOURNAMENT_PAYOUT_TOTAL
+///
+/// # 功能说明
+/// 同时创建锦标赛的奖池金库
+pub fn create_tournament(
+    ctx: Context<CreateTournament>,
+    id: u64,
+    start_slot: u64,
+    end_slot: u64,
+    entry_fee: u64,
+    payouts: [u16; TOURNAMENT_PRIZE_PLACES],
+) -> Result<()> {
+    {
+        let game = ctx.accounts.game_data_account.load()?;
+        if game.version() != GAME_DATA_VERSION {
+            return Err(SevenSeasError::AccountNeedsMigration.into());
+        }
+        if game.authority() != ctx.accounts.signer.key() {
+            return Err(SevenSeasError::Unauthorized.into());
+        }
+    }
+    if start_slot >= end_slot
+        || end_slot <= Clock::get()?.slot
+        || !Tournament::is_valid_payouts(&payouts)
+    {
+        return Err(SevenSeasError::InvalidTournament.into());
+    }
+
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.version = TOURNAMENT_VERSION;
+    tournament.id = id;
+    tournament.game_data_account = ctx.accounts.game_data_account.key();
+    tournament.authority = ctx.accounts.signer.key();
+    tournament.start_slot = start_slot;
+    tournament.end_slot = end_slot;
+    tournament.entry_fee = entry_fee;
+    tournament.payouts = payouts;
+    ctx.accounts.prize_vault.version = TOURNAMENT_VERSION;
+
+    msg!(
+        "Tournament {} from slot {} to {} with entry fee {}",
+        id,
+        start_slot,
+        end_slot,
+        entry_fee
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTournament<'info> {
+    /// 棋盘管理员
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 比赛所在的游戏数据账户
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 锦标赛账户
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"tournament", game_data_account.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Tournament::INIT_SPACE
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// 奖池金库
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"prize_vault", tournament.key().as_ref()],
+        bump,
+        space = 8 + PrizeVault::INIT_SPACE
+    )]
+    pub prize_vault: Account<'info, PrizeVault>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,23 +1,18 @@
 pub use crate::errors::SevenSeasError;
-use crate::{GameActionHistory, GameDataAccount};
-use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+use crate::{
+    GameActionHistory, GameBoard, GameConfig, GameDataAccount, GAME_ACTIONS_VERSION,
+    GAME_CONFIG_VERSION, PAUSE_CTHULHU,
 };
+use anchor_lang::prelude::*;
 
 pub fn cthulhu(ctx: Context<Cthulhu>) -> Result<()> {
-    let game = &mut ctx.accounts.game_data_account.load_mut()?;
+    ctx.accounts.config.ensure_not_paused(PAUSE_CTHULHU)?;
+    let game = &mut GameBoard::load_mut(&ctx.accounts.game_data_account)?;
 
     match game.cthulhu(
         ctx.accounts.player.to_account_info(),
         &mut ctx.accounts.game_actions,
         ctx.accounts.chest_vault.to_account_info(),
-        ctx.accounts.vault_token_account.to_account_info(),
-        ctx.accounts.player_token_account.to_account_info(),
-        ctx.accounts.token_account_owner_pda.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        ctx.bumps.token_account_owner_pda,
     ) {
         Ok(_val) => {}
         Err(err) => {
@@ -40,36 +35,21 @@ pub struct Cthulhu<'info> {
     pub chest_vault: AccountInfo<'info>,
     #[account(mut)]
     pub game_data_account: AccountLoader<'info, GameDataAccount>,
-    #[account(mut)]
-    pub game_actions: Account<'info, GameActionHistory>,
-    #[account(mut)]
-    pub player: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    /// CHECK:
-    pub token_account_owner: AccountInfo<'info>,
-    #[account(      
-        init_if_needed,
-        payer = player,
-        associated_token::mint = mint_of_token_being_sent,
-        associated_token::authority = token_account_owner      
-    )]
-    pub player_token_account: Account<'info, TokenAccount>,
     #[account(
         mut,
-        seeds=[b"token_vault".as_ref(), mint_of_token_being_sent.key().as_ref()],
-        token::mint=mint_of_token_being_sent,
-        token::authority=token_account_owner_pda,
-        bump
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    /// CHECK:
+    pub game_actions: Account<'info, GameActionHistory>,
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
     #[account(
-        mut,
-        seeds=[b"token_account_owner_pda".as_ref()],
-        bump
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
-    pub token_account_owner_pda: AccountInfo<'info>,
-    pub mint_of_token_being_sent: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub config: Account<'info, GameConfig>,
 }
@@ -0,0 +1,124 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    from_base_units, to_base_units, GameConfig, MintRegistry, Ship, GAME_CONFIG_VERSION,
+    MINT_REGISTRY_VERSION, PAUSE_EQUIP_CANNONS, SHIP_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount, Transfer},
+};
+
+/// 为船只装备大炮
+///
+/// # 参数
+/// * `amount` - 装备的大炮数量,按整数代币计
+///
+/// # 功能说明
+/// 把玩家的大炮代币托管到船载大炮金库,出海时按金库余额计算大炮数量。
+/// 船只出海期间不能装备,被击沉的船只需要先打捞。
+/// 装备后的大炮数量不能超过船型的炮位数量
+pub fn equip_cannons(ctx: Context<EquipCannons>, amount: u64) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_EQUIP_CANNONS)?;
+    if ctx.accounts.ship.deployed {
+        return Err(SevenSeasError::ShipIsDeployed.into());
+    }
+
+    let stats = ctx
+        .accounts
+        .ship
+        .class_stats()
+        .ok_or(SevenSeasError::InvalidShipClass)?;
+    let equipped = from_base_units(
+        ctx.accounts.ship_cannon_vault.amount,
+        ctx.accounts.cannon_mint.decimals,
+    );
+    if equipped.saturating_add(amount) > stats.cannon_slots {
+        return Err(SevenSeasError::CannonSlotsFull.into());
+    }
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.player_cannon_account.to_account_info(),
+            to: ctx.accounts.ship_cannon_vault.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        },
+    );
+    anchor_spl::token::transfer(
+        cpi_ctx,
+        to_base_units(amount, ctx.accounts.cannon_mint.decimals),
+    )?;
+
+    msg!("Equipped {} cannons", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct EquipCannons<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 船只账户
+    #[account(
+        seeds = [b"ship", nft_account.key().as_ref()],
+        bump,
+        constraint = ship.version == SHIP_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub ship: Account<'info, Ship>,
+
+    /// NFT账户,目前就是玩家EOA账户
+    /// CHECK: 只能为自己的船只装备
+    #[account(constraint = nft_account.key() == signer.key() @ SevenSeasError::Unauthorized)]
+    pub nft_account: AccountInfo<'info>,
+
+    /// 玩家大炮代币账户
+    #[account(
+        mut,
+        associated_token::mint = cannon_mint,
+        associated_token::authority = signer
+    )]
+    pub player_cannon_account: Account<'info, TokenAccount>,
+
+    /// 船载大炮金库
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [b"ship_cannons", ship.key().as_ref()],
+        bump,
+        token::mint = cannon_mint,
+        token::authority = token_account_owner_pda
+    )]
+    pub ship_cannon_vault: Account<'info, TokenAccount>,
+
+    /// 大炮代币铸币账户
+    #[account(address = mint_registry.cannon_mint @ SevenSeasError::InvalidMint)]
+    pub cannon_mint: Account<'info, Mint>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// CHECK: 船载金库的所有者PDA
+    #[account(
+        seeds = [b"token_account_owner_pda".as_ref()],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,70 @@
+pub use crate::errors::SevenSeasError;
+use crate::{PrizeVault, Tournament, TOURNAMENT_VERSION};
+use anchor_lang::prelude::*;
+
+/// 结束锦标赛并发放奖金
+///
+/// # 参数
+/// * `ctx` - 指令上下文,remaining_accounts 按名次传入获奖者的钱包
+///
+/// # 功能说明
+/// 1. 结束后任何人都可以调用,每个锦标赛只能结算一次
+/// 2. 按得分排名,得分相同时先报名的排在前面
+/// 3. 按奖金表从奖池金库向得分大于0的前几名发放奖金,传入的钱包必须与排名一致
+/// 4. 关闭奖池金库,租金和没有人获奖时的奖池退还给创建者
+pub fn finalize_tournament(ctx: Context<FinalizeTournament>) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    if Clock::get()?.slot < tournament.end_slot {
+        return Err(SevenSeasError::TournamentNotOver.into());
+    }
+    if tournament.finalized {
+        return Err(SevenSeasError::TournamentFinalized.into());
+    }
+
+    let prizes = tournament.prizes();
+    let ranking = tournament.ranking();
+    if ctx.remaining_accounts.len() != prizes.len() {
+        return Err(SevenSeasError::InvalidPrizeWinners.into());
+    }
+    let vault = ctx.accounts.prize_vault.to_account_info();
+    for ((winner, entry), prize) in ctx.remaining_accounts.iter().zip(&ranking).zip(prizes) {
+        if winner.key() != entry.player || !winner.is_writable {
+            return Err(SevenSeasError::InvalidPrizeWinners.into());
+        }
+        **vault.try_borrow_mut_lamports()? -= prize;
+        **winner.try_borrow_mut_lamports()? += prize;
+        msg!(
+            "{} won {} lamports with score {}",
+            entry.player,
+            prize,
+            entry.score
+        );
+    }
+    tournament.finalized = true;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeTournament<'info> {
+    pub signer: Signer<'info>,
+
+    /// 锦标赛账户
+    #[account(
+        mut,
+        constraint = tournament.version == TOURNAMENT_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// 奖池金库,发放奖金后关闭
+    #[account(
+        mut,
+        seeds = [b"prize_vault", tournament.key().as_ref()],
+        bump,
+        close = authority
+    )]
+    pub prize_vault: Account<'info, PrizeVault>,
+
+    /// 锦标赛的创建者,接收关闭奖池金库退还的余额
+    #[account(mut, address = tournament.authority @ SevenSeasError::Unauthorized)]
+    pub authority: SystemAccount<'info>,
+}
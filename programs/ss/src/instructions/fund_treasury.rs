@@ -0,0 +1,45 @@
+pub use crate::errors::SevenSeasError;
+use crate::{TreasuryAccount, TREASURY_VERSION};
+use anchor_lang::prelude::*;
+
+/// 向宝箱国库注资
+///
+/// # 参数
+/// * `amount` - 注资金额(lamports)
+///
+/// # 功能说明
+/// 任何人都可以注资,第一次注资时创建国库账户。
+/// spawn_chests 用国库中的SOL支付新宝箱的奖励
+pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+    ctx.accounts.treasury.version = TREASURY_VERSION;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.treasury.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, amount)?;
+
+    msg!("Treasury funded with {} lamports", amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    /// 宝箱国库,不存在时创建
+    #[account(
+        init_if_needed,
+        payer = funder,
+        seeds = [b"treasury"],
+        bump,
+        space = 8 + TreasuryAccount::INIT_SPACE
+    )]
+    pub treasury: Account<'info, TreasuryAccount>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,12 +1,13 @@
 // 导入必要的依赖
 pub use crate::errors::SevenSeasError;
-use crate::ChestVaultAccount;
+use crate::{ChestVaultAccount, CHEST_VAULT_VERSION};
 use anchor_lang::prelude::Account;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
 
 pub fn initialize(_ctx: Context<InitializeAccounts>) -> Result<()> {
     msg!("Initialized!");
+    _ctx.accounts.chest_vault.version = CHEST_VAULT_VERSION;
     Ok(())
 }
 
@@ -110,7 +111,7 @@ pub struct InitializeAccounts<'info> {
         seeds = [b"chestVault"],
         bump,
         payer = signer,
-        space = 8
+        space = 8 + ChestVaultAccount::INIT_SPACE
     )]
     pub chest_vault: Box<Account<'info, ChestVaultAccount>>,
 
@@ -0,0 +1,77 @@
+use crate::instructions::initialize_game_data::generate_and_fund;
+use crate::{
+    chunk_seed, BoardGenParams, ChestVaultAccount, GameDataAccount, DEFAULT_BOARD_HEIGHT,
+    DEFAULT_BOARD_WIDTH,
+};
+use anchor_lang::prelude::*;
+
+/// 初始化开放海域中的一个区块
+///
+/// # 参数
+/// * `chunk_x` - 区块在世界中的x坐标
+/// * `chunk_y` - 区块在世界中的y坐标
+/// * `params` - 可选的生成参数,其中的种子作为世界种子,每个区块的种子由区块坐标派生
+///
+/// # 功能说明
+/// 1. 区块是以 ("chunk", chunk_x, chunk_y) 为种子的游戏数据账户
+/// 2. 船只可以从区块边缘驶入相邻区块,炮弹也可以越过区块边缘
+pub fn initialize_chunk(
+    ctx: Context<InitializeChunk>,
+    chunk_x: u16,
+    chunk_y: u16,
+    params: Option<BoardGenParams>,
+) -> Result<()> {
+    msg!("Chunk {} {} Initialized!", chunk_x, chunk_y);
+    {
+        let mut game_data = ctx.accounts.new_game_data_account.load_init()?;
+        game_data.init(
+            ctx.accounts.signer.key(),
+            DEFAULT_BOARD_WIDTH,
+            DEFAULT_BOARD_HEIGHT,
+        );
+        game_data.set_chunk(chunk_x, chunk_y);
+    }
+
+    // 根据派生的区块种子生成棋盘
+    if let Some(params) = params {
+        let params = BoardGenParams {
+            seed: chunk_seed(params.seed, chunk_x, chunk_y),
+            ..params
+        };
+        generate_and_fund(
+            &ctx.accounts.new_game_data_account,
+            &params,
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.chest_vault.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(chunk_x: u16, chunk_y: u16)]
+pub struct InitializeChunk<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // 区块的游戏数据账户
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"chunk", chunk_x.to_le_bytes().as_ref(), chunk_y.to_le_bytes().as_ref()],
+        bump,
+        space = GameDataAccount::space(DEFAULT_BOARD_WIDTH, DEFAULT_BOARD_HEIGHT)
+    )]
+    pub new_game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    // 宝箱金库账户 - 存放初始宝箱的奖励
+    #[account(
+        mut,
+        seeds = [b"chestVault"],
+        bump
+    )]
+    pub chest_vault: Account<'info, ChestVaultAccount>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,31 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameConfig, GAME_CONFIG_VERSION};
+use anchor_lang::prelude::*;
+
+/// 初始化游戏配置账户,初始化者成为管理员
+pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+    msg!("Game Config Initialized!");
+    let config = &mut ctx.accounts.config;
+    config.version = GAME_CONFIG_VERSION;
+    config.authority = ctx.accounts.signer.key();
+    config.paused = 0;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // 游戏配置账户
+    #[account(
+        init,
+        payer = signer,
+        seeds = [b"config"],
+        bump,
+        space = 8 + GameConfig::INIT_SPACE
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    pub system_program: Program<'info, System>,
+}
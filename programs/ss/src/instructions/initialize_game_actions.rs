@@ -3,6 +3,7 @@ use anchor_lang::prelude::*;
 
 pub fn initialize_game_actions(_ctx: Context<InitializeGameActions>) -> Result<()> {
     msg!("Game Actions Account Initialized!");
+    _ctx.accounts.game_actions.init();
     Ok(())
 }
 
@@ -1,12 +1,63 @@
-use crate::GameDataAccount;
+use crate::{
+    BoardGenParams, ChestVaultAccount, GameBoard, GameDataAccount, CHEST_REWARD,
+    DEFAULT_BOARD_HEIGHT, DEFAULT_BOARD_WIDTH,
+};
 use anchor_lang::prelude::*;
 
-pub fn initialize_game_data(_ctx: Context<InitializeGameData>) -> Result<()> {
+/// 初始化游戏数据账户
+///
+/// # 参数
+/// * `params` - 可选的棋盘生成参数,为空时生成空棋盘
+///
+/// # 功能说明
+/// 1. 初始化空棋盘
+/// 2. 如果提供了生成参数,根据种子生成岛屿、暗礁和初始宝箱
+/// 3. 初始宝箱的奖励由初始化者支付到宝箱金库
+pub fn initialize_game_data(
+    _ctx: Context<InitializeGameData>,
+    params: Option<BoardGenParams>,
+) -> Result<()> {
     msg!("Game Data Account Initialized!");
-    // 初始化游戏数据账户
-    let game_data = &mut _ctx.accounts.new_game_data_account.load_init()?;
-    // 初始化棋盘
-    **game_data = GameDataAccount::default();
+    // 初始化游戏数据账户头部,初始化者成为管理员
+    _ctx.accounts.new_game_data_account.load_init()?.init(
+        _ctx.accounts.signer.key(),
+        DEFAULT_BOARD_WIDTH,
+        DEFAULT_BOARD_HEIGHT,
+    );
+
+    // 根据种子生成棋盘
+    if let Some(params) = params {
+        generate_and_fund(
+            &_ctx.accounts.new_game_data_account,
+            &params,
+            _ctx.accounts.signer.to_account_info(),
+            _ctx.accounts.chest_vault.to_account_info(),
+            _ctx.accounts.system_program.to_account_info(),
+        )?;
+    }
+    Ok(())
+}
+
+/// 在正在初始化的棋盘上生成地形和初始宝箱,并由初始化者支付宝箱奖励
+pub(crate) fn generate_and_fund<'info>(
+    game_data_account: &AccountLoader<'info, GameDataAccount>,
+    params: &BoardGenParams,
+    signer: AccountInfo<'info>,
+    chest_vault: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let chests = GameBoard::load_init(game_data_account)?.generate(params)?;
+    if chests > 0 {
+        let cpi_context = CpiContext::new(
+            system_program,
+            anchor_lang::system_program::Transfer {
+                from: signer,
+                to: chest_vault,
+            },
+        );
+        // 转移初始宝箱奖励到宝箱账户
+        anchor_lang::system_program::transfer(cpi_context, chests * CHEST_REWARD)?;
+    }
     Ok(())
 }
 
@@ -21,9 +72,17 @@ pub struct InitializeGameData<'info> {
         payer = signer,
         seeds = [b"level"],
         bump,
-        space = 10240
+        space = GameDataAccount::space(DEFAULT_BOARD_WIDTH, DEFAULT_BOARD_HEIGHT)
     )]
     pub new_game_data_account: AccountLoader<'info, GameDataAccount>,
 
+    // 宝箱金库账户 - 存放初始宝箱的奖励
+    #[account(
+        mut,
+        seeds = [b"chestVault"],
+        bump
+    )]
+    pub chest_vault: Account<'info, ChestVaultAccount>,
+
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,58 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameConfig, MintRegistry, GAME_CONFIG_VERSION, MINT_REGISTRY_VERSION};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+/// 初始化铸币注册表(仅管理员)
+///
+/// # 功能说明
+/// 登记金币、大炮和朗姆酒的铸币账户。注册表只能初始化一次,
+/// 之后所有指令都会检查传入的铸币账户是否与注册表一致
+pub fn initialize_mint_registry(ctx: Context<InitializeMintRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.mint_registry;
+    registry.version = MINT_REGISTRY_VERSION;
+    registry.gold_mint = ctx.accounts.gold_mint.key();
+    registry.cannon_mint = ctx.accounts.cannon_mint.key();
+    registry.rum_mint = ctx.accounts.rum_mint.key();
+    msg!(
+        "Mint registry initialized! gold {} cannon {} rum {}",
+        registry.gold_mint,
+        registry.cannon_mint,
+        registry.rum_mint
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeMintRegistry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以登记铸币账户
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    // 铸币注册表
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"mint_registry"],
+        bump,
+        space = 8 + MintRegistry::INIT_SPACE
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 金币铸币账户
+    pub gold_mint: Account<'info, Mint>,
+    /// 大炮铸币账户
+    pub cannon_mint: Account<'info, Mint>,
+    /// 朗姆酒铸币账户
+    pub rum_mint: Account<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
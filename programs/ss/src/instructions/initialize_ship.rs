@@ -1,13 +1,23 @@
 pub use crate::errors::SevenSeasError;
-use crate::Ship;
+use crate::{Ship, SHIP_CLASSES, SHIP_VERSION};
 use anchor_lang::prelude::Account;
 use anchor_lang::prelude::*;
 
-pub fn initialize_ship(ctx: Context<InitializeShip>) -> Result<()> {
+/// 初始化船只
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `class` - 船型,决定基础生命值、炮位数量、攻击范围、移动速度和升级上限
+pub fn initialize_ship(ctx: Context<InitializeShip>, class: u8) -> Result<()> {
+    let stats = SHIP_CLASSES
+        .get(class as usize)
+        .ok_or(SevenSeasError::InvalidShipClass)?;
     msg!("Ship Initialized!");
+    ctx.accounts.new_ship.version = SHIP_VERSION;
+    ctx.accounts.new_ship.class = class;
     // 设置初始生命值
-    ctx.accounts.new_ship.health = 50;
-    ctx.accounts.new_ship.start_health = 50;
+    ctx.accounts.new_ship.health = stats.base_health;
+    ctx.accounts.new_ship.start_health = stats.base_health;
     // 设置初始等级
     ctx.accounts.new_ship.level = 1;
     // 设置初始升级次数
@@ -28,7 +38,7 @@ pub struct InitializeShip<'info> {
         payer = signer, 
         seeds = [b"ship", nft_account.key().as_ref()],
         bump,
-        space = 1024
+        space = 8 + Ship::INIT_SPACE
     )]
     pub new_ship: Account<'info, Ship>,
     /// NFT账户
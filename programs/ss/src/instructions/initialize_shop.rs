@@ -0,0 +1,51 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameConfig, Shop, GAME_CONFIG_VERSION, SHOP_VERSION};
+use anchor_lang::prelude::*;
+
+/// 初始化商店(仅管理员)
+///
+/// # 参数
+/// * `cannon_price` - 每门大炮的价格(金币)
+/// * `rum_price` - 每瓶朗姆酒的价格(金币)
+/// * `daily_limit` - 每个钱包每天最多购买的商品数量
+pub fn initialize_shop(
+    ctx: Context<InitializeShop>,
+    cannon_price: u64,
+    rum_price: u64,
+    daily_limit: u64,
+) -> Result<()> {
+    let shop = &mut ctx.accounts.shop;
+    shop.version = SHOP_VERSION;
+    shop.cannon_price = cannon_price;
+    shop.rum_price = rum_price;
+    shop.daily_limit = daily_limit;
+    msg!("Shop initialized!");
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeShop<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以开设商店
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    // 商店账户
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"shop"],
+        bump,
+        space = 8 + Shop::INIT_SPACE
+    )]
+    pub shop: Account<'info, Shop>,
+
+    pub system_program: Program<'info, System>,
+}
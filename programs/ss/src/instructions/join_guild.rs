@@ -0,0 +1,52 @@
+pub use crate::errors::SevenSeasError;
+use crate::{Guild, PlayerProfile, GUILD_VERSION, PLAYER_PROFILE_VERSION};
+use anchor_lang::prelude::*;
+
+/// 加入公会
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+///
+/// # 功能说明
+/// 成员和会长都需要签名,成员不能已经属于其他公会,公会最多 GUILD_MAX_MEMBERS 名成员
+pub fn join_guild(ctx: Context<JoinGuild>) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    if profile.guild != 0 {
+        return Err(SevenSeasError::AlreadyInGuild.into());
+    }
+
+    let guild = &mut ctx.accounts.guild;
+    guild.add_member(ctx.accounts.member.key())?;
+    profile.guild = guild.id;
+
+    msg!("{} joined guild {}", ctx.accounts.member.key(), guild.id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinGuild<'info> {
+    /// 加入公会的玩家
+    pub member: Signer<'info>,
+
+    /// 会长,同意玩家加入
+    pub leader: Signer<'info>,
+
+    /// 公会账户
+    #[account(
+        mut,
+        seeds = [b"guild", guild.id.to_le_bytes().as_ref()],
+        bump,
+        has_one = leader @ SevenSeasError::Unauthorized,
+        constraint = guild.version == GUILD_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub guild: Account<'info, Guild>,
+
+    /// 玩家档案,记录所属的公会
+    #[account(
+        mut,
+        seeds = [b"profile", member.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+}
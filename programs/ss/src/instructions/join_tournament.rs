@@ -0,0 +1,74 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    GameConfig, PrizeVault, Tournament, GAME_CONFIG_VERSION, PAUSE_JOIN_TOURNAMENT,
+    TOURNAMENT_VERSION,
+};
+use anchor_lang::prelude::*;
+
+/// 报名参加锦标赛
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+///
+/// # 功能说明
+/// 结束前都可以报名,报名费存入奖池金库。报名前的击杀和宝箱不计分
+pub fn join_tournament(ctx: Context<JoinTournament>) -> Result<()> {
+    ctx.accounts
+        .config
+        .ensure_not_paused(PAUSE_JOIN_TOURNAMENT)?;
+    let tournament = &mut ctx.accounts.tournament;
+    if Clock::get()?.slot >= tournament.end_slot {
+        return Err(SevenSeasError::TournamentClosed.into());
+    }
+    tournament.add_entry(ctx.accounts.player.key())?;
+    tournament.prize_pool += tournament.entry_fee;
+
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.player.to_account_info(),
+            to: ctx.accounts.prize_vault.to_account_info(),
+        },
+    );
+    anchor_lang::system_program::transfer(cpi_context, tournament.entry_fee)?;
+
+    msg!(
+        "{} joined tournament {}, prize pool {}",
+        ctx.accounts.player.key(),
+        tournament.id,
+        tournament.prize_pool
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinTournament<'info> {
+    /// 参赛玩家,支付报名费
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 锦标赛账户
+    #[account(
+        mut,
+        constraint = tournament.version == TOURNAMENT_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// 奖池金库
+    #[account(
+        mut,
+        seeds = [b"prize_vault", tournament.key().as_ref()],
+        bump
+    )]
+    pub prize_vault: Account<'info, PrizeVault>,
+
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
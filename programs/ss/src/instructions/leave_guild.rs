@@ -0,0 +1,45 @@
+pub use crate::errors::SevenSeasError;
+use crate::{Guild, PlayerProfile, GUILD_VERSION, PLAYER_PROFILE_VERSION};
+use anchor_lang::prelude::*;
+
+/// 离开公会
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+///
+/// # 功能说明
+/// 会长离开时由最早加入的成员接任,公会账户在最后一名成员离开后保留,编号不会被重新使用。
+/// 已经在棋盘上的船只保留原来的队伍编号,直到下次出海
+pub fn leave_guild(ctx: Context<LeaveGuild>) -> Result<()> {
+    let guild = &mut ctx.accounts.guild;
+    guild.remove_member(&ctx.accounts.member.key())?;
+    ctx.accounts.profile.guild = 0;
+
+    msg!("{} left guild {}", ctx.accounts.member.key(), guild.id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LeaveGuild<'info> {
+    /// 离开公会的玩家
+    pub member: Signer<'info>,
+
+    /// 玩家所属的公会账户
+    #[account(
+        mut,
+        seeds = [b"guild", guild.id.to_le_bytes().as_ref()],
+        bump,
+        constraint = guild.version == GUILD_VERSION @ SevenSeasError::AccountNeedsMigration,
+        constraint = guild.id == profile.guild @ SevenSeasError::NotAGuildMember
+    )]
+    pub guild: Account<'info, Guild>,
+
+    /// 玩家档案,清除所属的公会
+    #[account(
+        mut,
+        seeds = [b"profile", member.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+}
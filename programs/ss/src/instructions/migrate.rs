@@ -0,0 +1,238 @@
+pub use crate::errors::SevenSeasError;
+use crate::instructions::resize_game_data::realloc_account;
+use crate::{
+    ChestVaultAccount, GameActionHistory, GameConfig, GameDataAccount, LegacyGameActionHistory,
+    LegacyShip, Ship, CHEST_VAULT_VERSION, GAME_ACTIONS_VERSION, GAME_CONFIG_VERSION,
+    GAME_DATA_VERSION, LEGACY_BOARD_SIZE, LEGACY_CHEST_VAULT_SPACE, LEGACY_GAME_ACTIONS_SPACE,
+    LEGACY_SHIP_SPACE, SHIP_CLASS_SLOOP, SHIP_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+/*
+迁移指令
+
+最初的账户没有版本号,无法按新结构体读取。迁移指令原地升级最初的账户:
+1. 按账户大小识别最初的布局
+2. 按新布局的大小 realloc,增大时由签名者补足租金
+3. 把旧数据转换为新布局并写入版本号
+
+迁移结果是确定的,任何人都可以为任何账户调用迁移指令。
+*/
+
+// 检查账户鉴别器
+fn check_discriminator(data: &[u8], discriminator: [u8; 8]) -> Result<()> {
+    if data.len() < 8 || data[..8] != discriminator {
+        return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    Ok(())
+}
+
+/// 迁移游戏数据账户(棋盘或开放海域区块)
+///
+/// 最初的10x10账户没有管理员,迁移后由游戏配置账户的管理员管理
+pub fn migrate_game_data(ctx: Context<MigrateGameData>) -> Result<()> {
+    let account = ctx.accounts.game_data_account.to_account_info();
+
+    {
+        let data = account.try_borrow_data()?;
+        check_discriminator(&data, GameDataAccount::discriminator())?;
+        if !GameDataAccount::is_legacy(&data) {
+            return Err(match data.get(8) {
+                Some(&GAME_DATA_VERSION) => SevenSeasError::AccountAlreadyMigrated,
+                _ => SevenSeasError::UnknownAccountLayout,
+            }
+            .into());
+        }
+    }
+
+    realloc_account(
+        &account,
+        GameDataAccount::space(LEGACY_BOARD_SIZE, LEGACY_BOARD_SIZE),
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+    GameDataAccount::migrate_legacy(
+        &mut account.try_borrow_mut_data()?,
+        ctx.accounts.config.authority,
+    )?;
+    msg!("Game data migrated to version {}", GAME_DATA_VERSION);
+    Ok(())
+}
+
+/// 迁移船只账户
+pub fn migrate_ship(ctx: Context<MigrateShip>) -> Result<()> {
+    let account = ctx.accounts.ship.to_account_info();
+
+    let legacy = {
+        let data = account.try_borrow_data()?;
+        check_discriminator(&data, Ship::discriminator())?;
+        if data.len() != LEGACY_SHIP_SPACE {
+            return Err(match data.get(8) {
+                Some(&SHIP_VERSION) => SevenSeasError::AccountAlreadyMigrated,
+                _ => SevenSeasError::UnknownAccountLayout,
+            }
+            .into());
+        }
+        // 最初的船只固定分配1024字节,没有版本号
+        LegacyShip::deserialize(&mut &data[8..])?
+    };
+
+    let ship = Ship {
+        version: SHIP_VERSION,
+        health: legacy.health,
+        kills: legacy.kills,
+        cannons: legacy.cannons,
+        upgrades: legacy.upgrades,
+        xp: legacy.xp,
+        level: legacy.level,
+        start_health: legacy.start_health,
+        deployed: false,
+        class: SHIP_CLASS_SLOOP,
+        board: Pubkey::default(),
+        board_epoch: 0,
+    };
+    // 新布局比旧的1024字节小,多出的租金留在账户中
+    account.realloc(8 + Ship::INIT_SPACE, false)?;
+    ship.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    msg!("Ship migrated to version {}", SHIP_VERSION);
+    Ok(())
+}
+
+/// 迁移游戏动作历史账户
+pub fn migrate_game_actions(ctx: Context<MigrateGameActions>) -> Result<()> {
+    let account = ctx.accounts.game_actions.to_account_info();
+
+    let game_actions = {
+        let data = account.try_borrow_data()?;
+        check_discriminator(&data, GameActionHistory::discriminator())?;
+        if data.len() != LEGACY_GAME_ACTIONS_SPACE {
+            return Err(match data.get(8) {
+                Some(&GAME_ACTIONS_VERSION) => SevenSeasError::AccountAlreadyMigrated,
+                _ => SevenSeasError::UnknownAccountLayout,
+            }
+            .into());
+        }
+        // 最初的历史记录没有版本号
+        let legacy = LegacyGameActionHistory::deserialize(&mut &data[8..])?;
+        GameActionHistory::from_legacy(legacy)
+    };
+
+    realloc_account(
+        &account,
+        8 + GameActionHistory::INIT_SPACE,
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+    game_actions.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    msg!("Game actions migrated to version {}", game_actions.version());
+    Ok(())
+}
+
+/// 迁移宝箱金库账户
+pub fn migrate_chest_vault(ctx: Context<MigrateChestVault>) -> Result<()> {
+    let account = ctx.accounts.chest_vault.to_account_info();
+
+    {
+        let data = account.try_borrow_data()?;
+        check_discriminator(&data, ChestVaultAccount::discriminator())?;
+        if data.len() != LEGACY_CHEST_VAULT_SPACE {
+            return Err(SevenSeasError::AccountAlreadyMigrated.into());
+        }
+    }
+
+    realloc_account(
+        &account,
+        8 + ChestVaultAccount::INIT_SPACE,
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.system_program.to_account_info(),
+    )?;
+    let vault = ChestVaultAccount {
+        version: CHEST_VAULT_VERSION,
+    };
+    vault.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    msg!("Chest vault migrated to version {}", CHEST_VAULT_VERSION);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateGameData<'info> {
+    /// 支付新增空间的租金
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户,旧布局无法按当前结构体加载
+    /// CHECK: 在指令中检查鉴别器和布局
+    #[account(mut, owner = crate::ID)]
+    pub game_data_account: AccountInfo<'info>,
+
+    /// 游戏配置账户,最初的账户迁移后以它的管理员作为棋盘管理员
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateShip<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 船只账户
+    /// CHECK: 在指令中检查鉴别器和布局
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"ship", nft_account.key().as_ref()],
+        bump
+    )]
+    pub ship: AccountInfo<'info>,
+
+    /// NFT账户
+    /// CHECK:
+    pub nft_account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateGameActions<'info> {
+    /// 支付新增空间的租金
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 游戏动作历史账户
+    /// CHECK: 在指令中检查鉴别器和布局
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"gameActions_history"],
+        bump
+    )]
+    pub game_actions: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateChestVault<'info> {
+    /// 支付新增空间的租金
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 宝箱金库账户
+    /// CHECK: 在指令中检查鉴别器和布局
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"chestVault"],
+        bump
+    )]
+    pub chest_vault: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -20,3 +20,81 @@ pub mod initialize_game_actions;
 pub use initialize_game_actions::*;
 pub mod initialize_game_data;
 pub use initialize_game_data::*;
+pub mod initialize_chunk;
+pub use initialize_chunk::*;
+pub mod resize_game_data;
+pub use resize_game_data::*;
+pub mod initialize_config;
+pub use initialize_config::*;
+pub mod set_paused;
+pub use set_paused::*;
+pub mod initialize_mint_registry;
+pub use initialize_mint_registry::*;
+pub mod equip_cannons;
+pub use equip_cannons::*;
+pub mod stock_rum;
+pub use stock_rum::*;
+pub mod salvage_ship;
+pub use salvage_ship::*;
+pub mod initialize_shop;
+pub use initialize_shop::*;
+pub mod update_shop;
+pub use update_shop::*;
+pub mod buy;
+pub use buy::*;
+pub mod claim_rewards;
+pub use claim_rewards::*;
+pub mod move_lite;
+pub use move_lite::*;
+pub mod update_profile;
+pub use update_profile::*;
+pub mod set_cooldowns;
+pub use set_cooldowns::*;
+pub mod repair;
+pub use repair::*;
+pub mod set_ammo_mints;
+pub use set_ammo_mints::*;
+pub mod set_wreck_percent;
+pub use set_wreck_percent::*;
+pub mod claim_loot;
+pub use claim_loot::*;
+pub mod set_loot_table;
+pub use set_loot_table::*;
+pub mod fund_treasury;
+pub use fund_treasury::*;
+pub mod set_chest_spawning;
+pub use set_chest_spawning::*;
+pub mod spawn_chests;
+pub use spawn_chests::*;
+pub mod spawn_npc;
+pub use spawn_npc::*;
+pub mod npc_tick;
+pub use npc_tick::*;
+pub mod create_guild;
+pub use create_guild::*;
+pub mod join_guild;
+pub use join_guild::*;
+pub mod leave_guild;
+pub use leave_guild::*;
+pub mod set_team_mode;
+pub use set_team_mode::*;
+pub mod place_bounty;
+pub use place_bounty::*;
+pub mod refund_bounty;
+pub use refund_bounty::*;
+pub mod create_tournament;
+pub use create_tournament::*;
+pub mod join_tournament;
+pub use join_tournament::*;
+pub mod finalize_tournament;
+pub use finalize_tournament::*;
+pub mod open_royale;
+pub use open_royale::*;
+pub mod start_royale;
+pub use start_royale::*;
+pub mod storm_tick;
+pub use storm_tick::*;
+pub mod claim_royale;
+pub use claim_royale::*;
+pub mod migrate;
+pub use migrate::*;
@@ -0,0 +1,51 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    GameActionHistory, GameBoard, GameConfig, GameDataAccount, GAME_ACTIONS_VERSION,
+    GAME_CONFIG_VERSION, PAUSE_MOVE_PLAYER,
+};
+use anchor_lang::prelude::*;
+
+/// 轻量移动指令
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `direction` - 移动方向,0-3分别代表上右下左
+///
+/// # 功能说明
+/// 只在棋盘内移动到空格子,不需要宝箱金库、玩家档案等账户,交易更小,消耗的计算单元更少。
+/// 游戏动作历史账户只读,用于读取移动冷却时间。
+/// 目标格子是宝箱或其他船只时返回 DestinationRequiresFullMove,需要改用move_player_v2
+pub fn move_lite(ctx: Context<MoveLite>, direction: u8) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_MOVE_PLAYER)?;
+    GameBoard::load_mut(&ctx.accounts.game_data_account)?.move_lite(
+        direction,
+        ctx.accounts.player.key,
+        &ctx.accounts.game_actions,
+    )
+}
+
+#[derive(Accounts)]
+pub struct MoveLite<'info> {
+    /// 玩家账户,必须是签名者
+    pub player: Signer<'info>,
+
+    /// 游戏数据账户,存储游戏状态
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 游戏动作历史账户,用于读取移动冷却时间
+    #[account(
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub game_actions: Account<'info, GameActionHistory>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
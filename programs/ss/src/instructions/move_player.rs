@@ -1,10 +1,12 @@
 pub use crate::errors::SevenSeasError;
-use crate::{GameActionHistory, GameDataAccount};
-use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+use crate::instructions::shoot::collect_bounties;
+use crate::{
+    ChestVaultAccount, GameActionHistory, GameBoard, GameConfig, GameDataAccount, LootTable,
+    MoveContext, PlayerProfile, Ship, Tournament, CHEST_VAULT_VERSION, GAME_ACTIONS_VERSION,
+    GAME_CONFIG_VERSION, LOOT_TABLE_VERSION, PAUSE_MOVE_PLAYER, PLAYER_PROFILE_VERSION,
+    SHIP_VERSION, TOURNAMENT_VERSION,
 };
+use anchor_lang::prelude::*;
 
 /// 移动玩家的指令处理函数
 ///
@@ -12,91 +14,180 @@ use anchor_spl::{
 /// * `ctx` - 指令上下文,包含所有需要的账户
 /// * `direction` - 移动方向,0-3分别代表上下左右
 ///
+/// 在开放海域中,从区块边缘驶出时会进入传入的相邻区块,传入相邻区块时必须同时传入船只账户。
+/// remaining_accounts 中可以传入悬赏账户,在接舷战中击沉悬赏目标时由胜者领取
+///
 /// # 返回值
 /// * `Result<()>` - 成功返回Ok(()),失败返回错误
 pub fn move_player_v2(ctx: Context<MovePlayer>, direction: u8) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_MOVE_PLAYER)?;
+    // 传入相邻区块时船只可能驶入相邻区块,必须同时传入船只账户以记录船只所在的棋盘
+    if ctx.accounts.neighbour_game_data_account.is_some() && ctx.accounts.ship.is_none() {
+        return Err(SevenSeasError::MissingShipAccount.into());
+    }
     // 获取游戏数据账户的可变引用
-    let game = &mut ctx.accounts.game_data_account.load_mut()?;
+    let game = &mut GameBoard::load_mut(&ctx.accounts.game_data_account)?;
+    // 开放海域中的相邻区块,用于驶入相邻区块
+    let mut neighbour = match &ctx.accounts.neighbour_game_data_account {
+        Some(loader) => Some(GameBoard::load_mut(loader)?),
+        None => None,
+    };
+
+    let chests = ctx.accounts.profile.chests_collected;
+    let kills = ctx.accounts.profile.kills;
+    let target_kills = ctx
+        .accounts
+        .target_profile
+        .as_ref()
+        .map(|target| target.kills);
 
     // 调用游戏逻辑处理移动
-    match game.move_in_direction(
+    game.move_in_direction(
         direction,
-        ctx.accounts.player.to_account_info(),
-        ctx.accounts.chest_vault.to_account_info(),
-        ctx.accounts.vault_token_account.to_account_info(),
-        ctx.accounts.player_token_account.to_account_info(),
-        ctx.accounts.token_account_owner_pda.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        ctx.bumps.token_account_owner_pda,
-        &mut ctx.accounts.game_actions,
-    ) {
-        Ok(_val) => {}
-        Err(err) => {
-            panic!("Error: {}", err);
-        }
-    }
+        MoveContext {
+            player: ctx.accounts.player.to_account_info(),
+            chest_vault: ctx.accounts.chest_vault.to_account_info(),
+            profile: &mut ctx.accounts.profile,
+            target_profile: ctx.accounts.target_profile.as_deref_mut(),
+            game_actions: &mut ctx.accounts.game_actions,
+            loot_table: ctx.accounts.loot_table.as_deref(),
+        },
+        neighbour.as_mut(),
+    )?;
     // 打印游戏状态
     game.print();
+
+    // 领取接舷战中被击沉船主的悬赏: 发起方获胜时SOL直接转给玩家,
+    // 守方获胜时SOL转入宝箱金库并托管在守方的船上
+    if !game.sunk().is_empty() {
+        let sunk = game.sunk().to_vec();
+        let now = Clock::get()?.slot;
+        if ctx.accounts.profile.kills > kills {
+            collect_bounties(
+                ctx.remaining_accounts,
+                &sunk,
+                now,
+                &ctx.accounts.player,
+                &mut ctx.accounts.profile,
+            )?;
+        } else if let Some(target) = &mut ctx.accounts.target_profile {
+            let lamports = collect_bounties(
+                ctx.remaining_accounts,
+                &sunk,
+                now,
+                &ctx.accounts.chest_vault.to_account_info(),
+                target,
+            )?;
+            game.escrow_reward(&target.owner, lamports)?;
+        }
+    }
+
+    // 驶入相邻区块时记录船只所在的棋盘
+    if let (Some(neighbour), Some(loader)) = (&neighbour, &ctx.accounts.neighbour_game_data_account)
+    {
+        if neighbour.is_on_board(ctx.accounts.player.key) {
+            let ship = ctx
+                .accounts
+                .ship
+                .as_mut()
+                .ok_or(SevenSeasError::MissingShipAccount)?;
+            ship.board = loader.key();
+            ship.board_epoch = neighbour.epoch();
+        }
+    }
+
+    // 锦标赛中每收集一个宝箱或在接舷战中击沉一艘船得一分,守方获胜时为守方计分
+    if let Some(tournament) = &mut ctx.accounts.tournament {
+        let now = Clock::get()?.slot;
+        let profile = &ctx.accounts.profile;
+        let points = profile.chests_collected - chests + profile.kills - kills;
+        tournament.record_score(&ctx.accounts.player.key(), points, now);
+        if let (Some(target), Some(kills)) = (&ctx.accounts.target_profile, target_kills) {
+            tournament.record_score(&target.owner, target.kills - kills, now);
+        }
+    }
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct MovePlayer<'info> {
     /// 宝箱金库账户,用于存储和发放奖励
-    /// CHECK: 这是一个简单的SOL账户,不需要额外检查
-    #[account(mut)]
-    pub chest_vault: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"chestVault"],
+        bump,
+        constraint = chest_vault.version == CHEST_VAULT_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub chest_vault: Account<'info, ChestVaultAccount>,
 
     /// 游戏数据账户,存储游戏状态
     #[account(mut)]
     pub game_data_account: AccountLoader<'info, GameDataAccount>,
 
+    /// 相邻区块的游戏数据账户,驶出区块边缘时需要传入
+    #[account(mut)]
+    pub neighbour_game_data_account: Option<AccountLoader<'info, GameDataAccount>>,
+
     /// 玩家账户,必须是签名者
     #[account(mut)]
     pub player: Signer<'info>,
 
-    /// CHECK: 这里等于player EOA
-    pub token_account_owner: AccountInfo<'info>,
-
-    pub system_program: Program<'info, System>,
-
-    /// 玩家的代币账户,用于接收奖励代币
-    #[account(      
-        init_if_needed,
-        payer = player,
-        associated_token::mint = mint_of_token_being_sent,
-        associated_token::authority = token_account_owner      
+    /// 玩家档案,记入移动和射击获得的金币
+    #[account(
+        mut,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    pub profile: Account<'info, PlayerProfile>,
 
-    /// 代币金库账户,用于存储游戏中的代币
+    /// 被撞击船只船主的玩家档案,与玩家的船只发生接舷战时需要传入
     #[account(
-        init_if_needed,
-        payer = player,
-        seeds=[b"token_vault".as_ref(), mint_of_token_being_sent.key().as_ref()],
-        token::mint=mint_of_token_being_sent,
-        token::authority=token_account_owner_pda,
-        bump
+        mut,
+        constraint = target_profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
+    pub target_profile: Option<Account<'info, PlayerProfile>>,
 
-    /// 代币账户所有者PDA
-    /// CHECK: 这是一个派生的PDA账户
+    /// 玩家的船只账户,驶入相邻区块时记录船只所在的棋盘,传入相邻区块时必须传入
     #[account(
         mut,
-        seeds=[b"token_account_owner_pda".as_ref()],
-        bump
+        seeds = [b"ship", player.key().as_ref()],
+        bump,
+        constraint = ship.version == SHIP_VERSION @ SevenSeasError::AccountNeedsMigration,
+        constraint = ship.board == game_data_account.key() @ SevenSeasError::WrongBoard
     )]
-    pub token_account_owner_pda: AccountInfo<'info>,
+    pub ship: Option<Account<'info, Ship>>,
 
-    /// 将要发送的代币的铸币账户
-    pub mint_of_token_being_sent: Account<'info, Mint>,
+    /// 游戏动作历史账户,用于记录游戏中的动作
+    #[account(
+        mut,
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub game_actions: Account<'info, GameActionHistory>,
 
-    pub token_program: Program<'info, Token>,
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
 
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// 宝箱掉落表,没有时收集宝箱只获得固定的金币奖励
+    #[account(
+        seeds = [b"loot_table"],
+        bump,
+        constraint = loot_table.version == LOOT_TABLE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub loot_table: Option<Account<'info, LootTable>>,
 
-    /// 游戏动作历史账户,用于记录游戏中的动作
-    #[account(mut)]
-    pub game_actions: Account<'info, GameActionHistory>,
+    /// 锦标赛账户,传入时比赛期间收集宝箱和接舷战击沉船只计分
+    #[account(
+        mut,
+        constraint = tournament.version == TOURNAMENT_VERSION @ SevenSeasError::AccountNeedsMigration,
+        constraint = tournament.game_data_account == game_data_account.key() @ SevenSeasError::InvalidTournament
+    )]
+    pub tournament: Option<Account<'info, Tournament>>,
 }
@@ -0,0 +1,44 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    GameActionHistory, GameBoard, GameConfig, GameDataAccount, GAME_ACTIONS_VERSION,
+    GAME_CONFIG_VERSION, PAUSE_NPC_TICK,
+};
+use anchor_lang::prelude::*;
+
+/// NPC行动一次,由keeper定时调用
+///
+/// # 功能说明
+/// 所有NPC按各自的行为使用和玩家相同的规则移动和开炮,任何人都可以调用
+pub fn npc_tick(ctx: Context<NpcTick>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_NPC_TICK)?;
+    GameBoard::load_mut(&ctx.accounts.game_data_account)?
+        .npc_tick(&mut ctx.accounts.game_actions)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct NpcTick<'info> {
+    /// 调用者,任何人都可以调用
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 游戏动作历史账户,提供冷却时间并记录NPC的动作
+    #[account(
+        mut,
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub game_actions: Account<'info, GameActionHistory>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
@@ -0,0 +1,25 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameBoard, GameDataAccount};
+use anchor_lang::prelude::*;
+
+/// 开放大逃杀报名(仅棋盘管理员)
+///
+/// # 功能说明
+/// 清除上一局的风暴,之后玩家通过 spawn_player 出海报名,
+/// 出海时托管的击杀奖励构成大逃杀的奖池
+pub fn open_royale(ctx: Context<OpenRoyale>) -> Result<()> {
+    if ctx.accounts.game_data_account.load()?.authority() != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+    GameBoard::load_mut(&ctx.accounts.game_data_account)?.open_royale()
+}
+
+#[derive(Accounts)]
+pub struct OpenRoyale<'info> {
+    /// 棋盘管理员
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+}
@@ -0,0 +1,144 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, Bounty, GameConfig, MintRegistry, BOUNTY_VERSION, GAME_CONFIG_VERSION,
+    MINT_REGISTRY_VERSION, PAUSE_PLACE_BOUNTY,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, Transfer};
+
+/// 悬赏指定玩家的船只
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `lamports` - 托管的SOL(lamports)
+/// * `gold` - 托管的金币(整数代币)
+/// * `duration_slots` - 有效期(slot数)
+///
+/// # 功能说明
+/// 1. SOL转入悬赏账户,金币转入金币金库
+/// 2. 同一悬赏人对同一目标的悬赏会累加,有效期取较晚的一个
+/// 3. 已过期但未退款的悬赏需要先通过 refund_bounty 取回
+pub fn place_bounty(
+    ctx: Context<PlaceBounty>,
+    lamports: u64,
+    gold: u64,
+    duration_slots: u64,
+) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_PLACE_BOUNTY)?;
+    if (lamports == 0 && gold == 0)
+        || duration_slots == 0
+        || ctx.accounts.target.key() == ctx.accounts.placer.key()
+    {
+        return Err(SevenSeasError::InvalidBounty.into());
+    }
+
+    let now = Clock::get()?.slot;
+    let bounty = &mut ctx.accounts.bounty;
+    if bounty.version == 0 {
+        bounty.version = BOUNTY_VERSION;
+        bounty.placer = ctx.accounts.placer.key();
+        bounty.target = ctx.accounts.target.key();
+    } else if bounty.is_funded() && !bounty.is_active(now) {
+        return Err(SevenSeasError::BountyExpired.into());
+    }
+    bounty.lamports += lamports;
+    bounty.gold += gold;
+    bounty.expiry_slot = bounty.expiry_slot.max(now + duration_slots);
+
+    // 1. 托管SOL
+    if lamports > 0 {
+        let cpi_context = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.placer.to_account_info(),
+                to: ctx.accounts.bounty.to_account_info(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, lamports)?;
+    }
+
+    // 2. 托管金币
+    if gold > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.placer_gold_account.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.placer.to_account_info(),
+            },
+        );
+        anchor_spl::token::transfer(
+            cpi_ctx,
+            to_base_units(gold, ctx.accounts.gold_mint.decimals),
+        )?;
+    }
+
+    msg!(
+        "Bounty on {} of {} lamports and {} gold until slot {}",
+        ctx.accounts.target.key(),
+        ctx.accounts.bounty.lamports,
+        ctx.accounts.bounty.gold,
+        ctx.accounts.bounty.expiry_slot
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PlaceBounty<'info> {
+    /// 悬赏人
+    #[account(mut)]
+    pub placer: Signer<'info>,
+
+    /// CHECK: 目标玩家的钱包,只用作种子
+    pub target: AccountInfo<'info>,
+
+    /// 悬赏账户,如果不存在则创建
+    #[account(
+        init_if_needed,
+        payer = placer,
+        seeds = [b"bounty", target.key().as_ref(), placer.key().as_ref()],
+        bump,
+        space = 8 + Bounty::INIT_SPACE
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    /// 铸币注册表,用于校验金币铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 悬赏人的金币代币账户
+    #[account(
+        mut,
+        associated_token::mint = gold_mint,
+        associated_token::authority = placer
+    )]
+    pub placer_gold_account: Account<'info, TokenAccount>,
+
+    /// 金币金库
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), gold_mint.key().as_ref()],
+        bump,
+        token::mint = gold_mint
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// 金币铸币账户
+    #[account(address = mint_registry.gold_mint @ SevenSeasError::InvalidMint)]
+    pub gold_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
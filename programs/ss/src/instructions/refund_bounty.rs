@@ -0,0 +1,123 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, Bounty, GameConfig, MintRegistry, BOUNTY_VERSION, GAME_CONFIG_VERSION,
+    MINT_REGISTRY_VERSION, PAUSE_REFUND_BOUNTY,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount, Transfer},
+};
+
+/// 取回过期的悬赏并关闭悬赏账户
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+///
+/// # 功能说明
+/// 悬赏过期或已被领取后,悬赏人取回未被领取的金币,
+/// 关闭悬赏账户时SOL连同租金一起退还给悬赏人
+pub fn refund_bounty(ctx: Context<RefundBounty>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_REFUND_BOUNTY)?;
+    let bounty = &ctx.accounts.bounty;
+    if bounty.is_active(Clock::get()?.slot) {
+        return Err(SevenSeasError::BountyNotRefundable.into());
+    }
+
+    if bounty.gold > 0 {
+        let seeds = &[
+            b"token_account_owner_pda".as_ref(),
+            &[ctx.bumps.token_account_owner_pda],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.placer_gold_account.to_account_info(),
+                authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+            },
+            signer,
+        );
+        anchor_spl::token::transfer(
+            cpi_ctx,
+            to_base_units(bounty.gold, ctx.accounts.gold_mint.decimals),
+        )?;
+    }
+
+    msg!(
+        "Refunded bounty on {}: {} lamports and {} gold",
+        bounty.target,
+        bounty.lamports,
+        bounty.gold
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundBounty<'info> {
+    /// 悬赏人
+    #[account(mut)]
+    pub placer: Signer<'info>,
+
+    /// 悬赏账户,退款后关闭
+    #[account(
+        mut,
+        seeds = [b"bounty", bounty.target.as_ref(), placer.key().as_ref()],
+        bump,
+        has_one = placer @ SevenSeasError::Unauthorized,
+        constraint = bounty.version == BOUNTY_VERSION @ SevenSeasError::AccountNeedsMigration,
+        close = placer
+    )]
+    pub bounty: Account<'info, Bounty>,
+
+    /// 悬赏人的金币代币账户,不存在时创建
+    #[account(
+        init_if_needed,
+        payer = placer,
+        associated_token::mint = gold_mint,
+        associated_token::authority = placer
+    )]
+    pub placer_gold_account: Account<'info, TokenAccount>,
+
+    /// 金币金库
+    #[account(
+        mut,
+        seeds = [b"token_vault".as_ref(), gold_mint.key().as_ref()],
+        bump,
+        token::mint = gold_mint,
+        token::authority = token_account_owner_pda
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: 金币金库的所有者PDA
+    #[account(
+        seeds = [b"token_account_owner_pda".as_ref()],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
+    /// 金币铸币账户
+    #[account(address = mint_registry.gold_mint @ SevenSeasError::InvalidMint)]
+    pub gold_mint: Account<'info, Mint>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
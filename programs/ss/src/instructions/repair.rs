@@ -0,0 +1,86 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, GameActionHistory, GameBoard, GameConfig, GameDataAccount, MintRegistry,
+    GAME_ACTIONS_VERSION, GAME_CONFIG_VERSION, MINT_REGISTRY_VERSION, PAUSE_REPAIR,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Burn, Mint, Token, TokenAccount};
+
+/// 用朗姆酒修理船只
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `amount` - 最多消耗的朗姆酒数量,按整数代币计
+///
+/// # 功能说明
+/// 1. 在棋盘上恢复船只的生命值,最多恢复到初始生命值
+/// 2. 从玩家的朗姆酒代币账户中销毁实际消耗的朗姆酒
+pub fn repair(ctx: Context<Repair>, amount: u64) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_REPAIR)?;
+
+    let used = GameBoard::load_mut(&ctx.accounts.game_data_account)?.repair(
+        ctx.accounts.player.key,
+        amount,
+        &mut ctx.accounts.game_actions,
+    )?;
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.rum_mint.to_account_info(),
+            from: ctx.accounts.player_rum_account.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        },
+    );
+    anchor_spl::token::burn(cpi_ctx, to_base_units(used, ctx.accounts.rum_mint.decimals))?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Repair<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 游戏数据账户,船只所在的棋盘
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 游戏动作历史账户,记录修理动作
+    #[account(
+        mut,
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub game_actions: Account<'info, GameActionHistory>,
+
+    /// 玩家朗姆酒代币账户
+    #[account(
+        mut,
+        associated_token::mint = rum_mint,
+        associated_token::authority = player
+    )]
+    pub player_rum_account: Account<'info, TokenAccount>,
+
+    /// 朗姆酒代币铸币账户
+    #[account(mut, address = mint_registry.rum_mint @ SevenSeasError::InvalidMint)]
+    pub rum_mint: Account<'info, Mint>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
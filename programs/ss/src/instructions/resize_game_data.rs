@@ -0,0 +1,95 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameBoard, GameDataAccount, GAME_DATA_VERSION, MAX_BOARD_SIZE};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+
+/// 扩容游戏数据账户
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `width` - 新的棋盘宽度
+/// * `height` - 新的棋盘高度
+///
+/// # 功能说明
+/// 1. 只有管理员可以扩容,棋盘只能变大
+/// 2. 单条指令最多只能增加 MAX_PERMITTED_DATA_INCREASE(10240)字节,
+///    大棋盘(如 32x32、64x64)需要重复调用,直到账户达到目标大小
+/// 3. 账户达到目标大小后,把现有格子移动到新布局中的位置
+pub fn resize_game_data(ctx: Context<ResizeGameData>, width: u16, height: u16) -> Result<()> {
+    let (version, authority, (old_width, old_height)) = {
+        let game = ctx.accounts.game_data_account.load()?;
+        (game.version(), game.authority(), game.size())
+    };
+    if version != GAME_DATA_VERSION {
+        return Err(SevenSeasError::AccountNeedsMigration.into());
+    }
+    if authority != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+    if width > MAX_BOARD_SIZE
+        || height > MAX_BOARD_SIZE
+        || width < old_width
+        || height < old_height
+        || (width == old_width && height == old_height)
+    {
+        return Err(SevenSeasError::InvalidBoardSize.into());
+    }
+
+    let account = ctx.accounts.game_data_account.to_account_info();
+    let target_len = GameDataAccount::space(width, height);
+    let new_len = target_len.min(account.data_len() + MAX_PERMITTED_DATA_INCREASE);
+
+    if account.data_len() < new_len {
+        realloc_account(
+            &account,
+            new_len,
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+    }
+
+    // 还没有达到目标大小,需要再次调用
+    if new_len < target_len {
+        msg!("Game data grown to {} of {} bytes", new_len, target_len);
+        return Ok(());
+    }
+
+    GameBoard::resize(&ctx.accounts.game_data_account, width, height)
+}
+
+/// 调整账户大小,增大时由付款人补足租金豁免所需的lamports
+pub(crate) fn realloc_account<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let rent_needed = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_needed > 0 {
+        let cpi_context = CpiContext::new(
+            system_program,
+            anchor_lang::system_program::Transfer {
+                from: payer,
+                to: account.clone(),
+            },
+        );
+        anchor_lang::system_program::transfer(cpi_context, rent_needed)?;
+    }
+    account.realloc(new_len, true)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResizeGameData<'info> {
+    /// 管理员,支付新增空间的租金
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,173 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    to_base_units, GameConfig, GameDataAccount, MintRegistry, PlayerProfile, Ship,
+    GAME_CONFIG_VERSION, MINT_REGISTRY_VERSION, PAUSE_SALVAGE_SHIP, PLAYER_PROFILE_VERSION,
+    SHIP_SINK_LOSS_PERCENT, SHIP_VERSION,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Burn, Mint, Token, TokenAccount};
+
+/// 打捞被击沉的船只
+///
+/// # 功能说明
+/// 1. 船只已出海但不在出海时记录的棋盘上,说明已被击沉
+/// 2. 按 SHIP_SINK_LOSS_PERCENT 销毁装上船的大炮和船载的朗姆酒
+/// 3. 在玩家档案中记录一次死亡
+/// 4. 清除出海标记,之后可以重新装备大炮和补充朗姆酒
+///
+/// 棋盘重置时被移除的船只没有被击沉,只清除出海标记
+pub fn salvage_ship(ctx: Context<SalvageShip>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_SALVAGE_SHIP)?;
+    let (on_board, epoch) = {
+        let game = ctx.accounts.game_data_account.load()?;
+        (game.is_on_board(ctx.accounts.signer.key), game.epoch())
+    };
+    if !ctx.accounts.ship.deployed || on_board {
+        return Err(SevenSeasError::ShipIsDeployed.into());
+    }
+
+    if ctx.accounts.ship.board_epoch == epoch {
+        sink_ship_cargo(
+            ctx.accounts.ship.cannons,
+            (&ctx.accounts.ship_cannon_vault, &ctx.accounts.cannon_mint),
+            (&ctx.accounts.ship_rum_vault, &ctx.accounts.rum_mint),
+            ctx.accounts.token_account_owner_pda.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.bumps.token_account_owner_pda,
+        )?;
+        ctx.accounts.profile.record_death();
+    }
+    ctx.accounts.ship.deployed = false;
+    ctx.accounts.ship.board = Pubkey::default();
+    Ok(())
+}
+
+/// 销毁被击沉船只的一部分船载代币
+///
+/// 船只被击沉时攻击者的交易中没有船只账户,损失在船只下次出海或打捞时结算。
+/// 大炮按出海时装上船的数量计算,与残骸中留下的大炮数量一致。
+/// 船载金库和对应的铸币账户成对传入
+pub(crate) fn sink_ship_cargo<'info>(
+    cannons: u64,
+    (cannon_vault, cannon_mint): (&Account<'info, TokenAccount>, &Account<'info, Mint>),
+    (rum_vault, rum_mint): (&Account<'info, TokenAccount>, &Account<'info, Mint>),
+    token_account_owner_pda: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    token_owner_bump: u8,
+) -> Result<()> {
+    let seeds = &[b"token_account_owner_pda".as_ref(), &[token_owner_bump]];
+    let signer = &[&seeds[..]];
+
+    let cannon_loss = to_base_units(cannons * SHIP_SINK_LOSS_PERCENT / 100, cannon_mint.decimals)
+        .min(cannon_vault.amount);
+    let rum_loss = rum_vault.amount * SHIP_SINK_LOSS_PERCENT / 100;
+    for (vault, mint, loss) in [
+        (cannon_vault, cannon_mint, cannon_loss),
+        (rum_vault, rum_mint, rum_loss),
+    ] {
+        if loss == 0 {
+            continue;
+        }
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program.clone(),
+            Burn {
+                mint: mint.to_account_info(),
+                from: vault.to_account_info(),
+                authority: token_account_owner_pda.clone(),
+            },
+            signer,
+        );
+        anchor_spl::token::burn(cpi_ctx, loss)?;
+    }
+    msg!(
+        "Ship sunk, {}% of its cargo was lost",
+        SHIP_SINK_LOSS_PERCENT
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SalvageShip<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// 船只账户
+    #[account(
+        mut,
+        seeds = [b"ship", nft_account.key().as_ref()],
+        bump,
+        constraint = ship.version == SHIP_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub ship: Account<'info, Ship>,
+
+    /// NFT账户,目前就是玩家EOA账户
+    /// CHECK: 只能打捞自己的船只
+    #[account(constraint = nft_account.key() == signer.key() @ SevenSeasError::Unauthorized)]
+    pub nft_account: AccountInfo<'info>,
+
+    /// 玩家档案,记录死亡次数
+    #[account(
+        mut,
+        seeds = [b"profile", signer.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    /// 船只最后出海的棋盘,用于确认船只已不在棋盘上
+    #[account(constraint = game_data_account.key() == ship.board @ SevenSeasError::WrongBoard)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 船载大炮金库
+    #[account(
+        mut,
+        seeds = [b"ship_cannons", ship.key().as_ref()],
+        bump,
+        token::mint = cannon_mint,
+        token::authority = token_account_owner_pda
+    )]
+    pub ship_cannon_vault: Account<'info, TokenAccount>,
+
+    /// 船载朗姆酒金库
+    #[account(
+        mut,
+        seeds = [b"ship_rum", ship.key().as_ref()],
+        bump,
+        token::mint = rum_mint,
+        token::authority = token_account_owner_pda
+    )]
+    pub ship_rum_vault: Account<'info, TokenAccount>,
+
+    /// 大炮代币铸币账户
+    #[account(mut, address = mint_registry.cannon_mint @ SevenSeasError::InvalidMint)]
+    pub cannon_mint: Account<'info, Mint>,
+
+    /// 朗姆酒代币铸币账户
+    #[account(mut, address = mint_registry.rum_mint @ SevenSeasError::InvalidMint)]
+    pub rum_mint: Account<'info, Mint>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// CHECK: 船载金库的所有者PDA
+    #[account(
+        seeds = [b"token_account_owner_pda".as_ref()],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
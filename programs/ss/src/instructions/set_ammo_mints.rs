@@ -0,0 +1,52 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameConfig, MintRegistry, GAME_CONFIG_VERSION, MINT_REGISTRY_VERSION};
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+/// 登记特殊弹药的铸币账户(仅管理员)
+///
+/// # 功能说明
+/// 登记链弹、霰弹和爆破弹的铸币账户,射击时只接受这里登记的弹药代币
+pub fn set_ammo_mints(ctx: Context<SetAmmoMints>) -> Result<()> {
+    let registry = &mut ctx.accounts.mint_registry;
+    registry.chain_shot_mint = ctx.accounts.chain_shot_mint.key();
+    registry.grapeshot_mint = ctx.accounts.grapeshot_mint.key();
+    registry.explosive_shell_mint = ctx.accounts.explosive_shell_mint.key();
+    msg!(
+        "Ammo mints set! chain shot {} grapeshot {} explosive shell {}",
+        registry.chain_shot_mint,
+        registry.grapeshot_mint,
+        registry.explosive_shell_mint
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetAmmoMints<'info> {
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以登记铸币账户
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    // 铸币注册表
+    #[account(
+        mut,
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 链弹铸币账户
+    pub chain_shot_mint: Account<'info, Mint>,
+    /// 霰弹铸币账户
+    pub grapeshot_mint: Account<'info, Mint>,
+    /// 爆破弹铸币账户
+    pub explosive_shell_mint: Account<'info, Mint>,
+}
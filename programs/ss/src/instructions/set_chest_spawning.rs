@@ -0,0 +1,43 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameDataAccount, GAME_DATA_VERSION};
+use anchor_lang::prelude::*;
+
+/// 设置由 spawn_chests 维持的宝箱密度(仅棋盘管理员)
+///
+/// # 参数
+/// * `chest_density` - 每1000个水域格子中的目标宝箱数
+/// * `max_chests` - 棋盘上最多的宝箱数,为0时关闭
+///
+/// # 功能说明
+/// 开启后生成玩家时不再附带生成宝箱,也不再向玩家收取宝箱奖励,
+/// 宝箱改由 spawn_chests 补充并从宝箱国库中支付奖励
+pub fn set_chest_spawning(
+    ctx: Context<SetChestSpawning>,
+    chest_density: u16,
+    max_chests: u16,
+) -> Result<()> {
+    let mut game = ctx.accounts.game_data_account.load_mut()?;
+    if game.version() != GAME_DATA_VERSION {
+        return Err(SevenSeasError::AccountNeedsMigration.into());
+    }
+    if game.authority() != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+    game.set_chest_spawning(chest_density, max_chests);
+    msg!(
+        "Chest density set to {} per 1000 tiles, at most {} chests",
+        chest_density,
+        max_chests
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetChestSpawning<'info> {
+    /// 棋盘管理员
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+}
@@ -0,0 +1,51 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameActionHistory, GameConfig, GAME_ACTIONS_VERSION, GAME_CONFIG_VERSION};
+use anchor_lang::prelude::*;
+
+/// 设置射击和移动的冷却时间
+///
+/// # 参数
+/// * `shoot_cooldown_slots` - 两次射击之间至少间隔的slot数,为0时不限制
+/// * `move_cooldown_slots` - 速度为1的船只两次移动之间至少间隔的slot数,为0时不限制
+///
+/// # 功能说明
+/// 冷却时间记录在游戏动作历史账户中,射击和移动指令本来就会传入这个账户,
+/// 客户端也可以直接读取它来显示装填时间
+pub fn set_cooldowns(
+    ctx: Context<SetCooldowns>,
+    shoot_cooldown_slots: u64,
+    move_cooldown_slots: u64,
+) -> Result<()> {
+    ctx.accounts
+        .game_actions
+        .set_cooldowns(shoot_cooldown_slots, move_cooldown_slots);
+    msg!(
+        "Cooldowns set to {} slots for shooting and {} slots for moving",
+        shoot_cooldown_slots,
+        move_cooldown_slots
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCooldowns<'info> {
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以修改冷却时间
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    /// 游戏动作历史账户,存储冷却时间
+    #[account(
+        mut,
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub game_actions: Account<'info, GameActionHistory>,
+}
@@ -0,0 +1,60 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    ChestTierConfig, GameConfig, LootTable, CHEST_TIER_COUNT, GAME_CONFIG_VERSION,
+    LOOT_TABLE_VERSION,
+};
+use anchor_lang::prelude::*;
+
+/// 设置宝箱掉落表(仅管理员)
+///
+/// # 参数
+/// * `tiers` - 普通、稀有和传说宝箱的生成权重、金币奖励和掉落条目
+///
+/// # 功能说明
+/// 第一次调用时创建掉落表,之后覆盖原有配置。
+/// 已经在棋盘上的宝箱保留生成时的等级,收集时按新的配置发放奖励
+pub fn set_loot_table(
+    ctx: Context<SetLootTable>,
+    tiers: [ChestTierConfig; CHEST_TIER_COUNT],
+) -> Result<()> {
+    if !LootTable::is_valid(&tiers) {
+        return Err(SevenSeasError::InvalidLootItem.into());
+    }
+    let loot_table = &mut ctx.accounts.loot_table;
+    loot_table.version = LOOT_TABLE_VERSION;
+    loot_table.tiers = tiers;
+    msg!(
+        "Loot table set! spawn weights {} / {} / {}",
+        tiers[0].spawn_weight,
+        tiers[1].spawn_weight,
+        tiers[2].spawn_weight
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetLootTable<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以设置掉落表
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    /// 宝箱掉落表,不存在时创建
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"loot_table"],
+        bump,
+        space = 8 + LootTable::INIT_SPACE
+    )]
+    pub loot_table: Account<'info, LootTable>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,34 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameConfig, GAME_CONFIG_VERSION, PAUSE_ALL};
+use anchor_lang::prelude::*;
+
+/// 设置暂停位图
+///
+/// # 参数
+/// * `paused` - 新的暂停位图,见 PAUSE_* 常量,为0时恢复所有指令
+///
+/// # 功能说明
+/// 发现漏洞时管理员可以单独停用某几条指令,无需重新部署程序
+pub fn set_paused(ctx: Context<SetPaused>, paused: u32) -> Result<()> {
+    if paused & !PAUSE_ALL != 0 {
+        return Err(SevenSeasError::InvalidPauseFlags.into());
+    }
+    ctx.accounts.config.paused = paused;
+    msg!("Paused flags set to {:#034b}", paused);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以修改
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
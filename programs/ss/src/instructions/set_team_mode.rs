@@ -0,0 +1,35 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameDataAccount, GAME_DATA_VERSION};
+use anchor_lang::prelude::*;
+
+/// 开启或关闭团队模式(仅棋盘管理员)
+///
+/// # 参数
+/// * `enabled` - 是否开启
+///
+/// # 功能说明
+/// 团队模式中出海的船只以所属公会编号作为队伍编号,射击和撞击不会伤到队友,
+/// 击杀和收集宝箱为队伍加一分。开启时清空上一局的队伍得分,
+/// 已经在棋盘上的船只需要重新出海才会加入队伍
+pub fn set_team_mode(ctx: Context<SetTeamMode>, enabled: bool) -> Result<()> {
+    let mut game = ctx.accounts.game_data_account.load_mut()?;
+    if game.version() != GAME_DATA_VERSION {
+        return Err(SevenSeasError::AccountNeedsMigration.into());
+    }
+    if game.authority() != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+    game.set_team_mode(enabled);
+    msg!("Team mode set to {}", enabled);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTeamMode<'info> {
+    /// 棋盘管理员
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+}
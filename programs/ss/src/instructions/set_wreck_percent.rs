@@ -0,0 +1,34 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameDataAccount, GAME_DATA_VERSION};
+use anchor_lang::prelude::*;
+
+/// 设置被击沉的船只留下的残骸(仅棋盘管理员)
+///
+/// # 参数
+/// * `percent` - 留在残骸中的托管奖励比例(百分比),为0时不留残骸
+///
+/// # 功能说明
+/// 开启后被击沉的船只在原地留下残骸宝箱,攻击者只获得剩余的托管奖励。
+/// 残骸同时装有船主会损失的那部分大炮,任何船只都可以像收集宝箱一样收集残骸
+pub fn set_wreck_percent(ctx: Context<SetWreckPercent>, percent: u8) -> Result<()> {
+    let mut game = ctx.accounts.game_data_account.load_mut()?;
+    if game.version() != GAME_DATA_VERSION {
+        return Err(SevenSeasError::AccountNeedsMigration.into());
+    }
+    if game.authority() != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+    game.set_wreck_percent(percent)?;
+    msg!("Wreck percent set to {}", percent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetWreckPercent<'info> {
+    /// 棋盘管理员
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+}
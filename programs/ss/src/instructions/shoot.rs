@@ -1,74 +1,269 @@
 pub use crate::errors::SevenSeasError;
-use crate::{GameActionHistory, GameDataAccount};
-use anchor_lang::prelude::*;
-use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+use crate::{
+    to_base_units, Bounty, ChestVaultAccount, GameActionHistory, GameBoard, GameConfig,
+    GameDataAccount, MintRegistry, PlayerProfile, Tournament, AMMO_ROUND_SHOT, CHEST_VAULT_VERSION,
+    GAME_ACTIONS_VERSION, GAME_CONFIG_VERSION, MINT_REGISTRY_VERSION, PAUSE_SHOOT,
+    PLAYER_PROFILE_VERSION, TOURNAMENT_VERSION,
 };
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{Burn, Mint, Token, TokenAccount};
+
+/// 射击
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `ammo` - 弹药类型,见 AMMO_* 常量
+///
+/// # 功能说明
+/// 普通炮弹不消耗代币,其余弹药每次射击从玩家的弹药代币账户中销毁一个代币。
+/// remaining_accounts 中可以传入悬赏账户和队友的钱包,同一账户不能重复传入:
+/// 击沉悬赏目标时领取悬赏;团队模式中击杀奖励与附近的队友平分,
+/// 附近的队友由棋盘上的玩家表确定,击杀时必须传入每个附近队友的钱包
+pub fn shoot<'info>(ctx: Context<'_, '_, '_, 'info, Shoot<'info>>, ammo: u8) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_SHOOT)?;
+    // 同一账户不能在 remaining_accounts 中重复传入
+    let accounts = ctx.remaining_accounts;
+    for (i, account) in accounts.iter().enumerate() {
+        if accounts[..i].iter().any(|other| other.key == account.key) {
+            return Err(SevenSeasError::DuplicateAccount.into());
+        }
+    }
+    if ammo != AMMO_ROUND_SHOT {
+        consume_ammo(&ctx, ammo)?;
+    }
 
-pub fn shoot(ctx: Context<Shoot>) -> Result<()> {
-    let game = &mut ctx.accounts.game_data_account.load_mut()?;
+    let game = &mut GameBoard::load_mut(&ctx.accounts.game_data_account)?;
+    // 开放海域中的相邻区块,用于跨区块射击
+    let mut neighbour = match &ctx.accounts.neighbour_game_data_account {
+        Some(loader) => Some(GameBoard::load_mut(loader)?),
+        None => None,
+    };
 
-    match game.shoot(
+    let kills = ctx.accounts.profile.kills;
+    let reward = game.shoot(
+        ammo,
         ctx.accounts.player.to_account_info(),
         &mut ctx.accounts.game_actions,
         ctx.accounts.chest_vault.to_account_info(),
-        ctx.accounts.vault_token_account.to_account_info(),
-        ctx.accounts.player_token_account.to_account_info(),
-        ctx.accounts.token_account_owner_pda.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        ctx.bumps.token_account_owner_pda,
-    ) {
-        Ok(_val) => {}
-        Err(err) => {
-            panic!("Error: {}", err);
+        &mut ctx.accounts.profile,
+        neighbour.as_mut(),
+    )?;
+    game.print();
+
+    // 锦标赛中每击沉一艘船得一分
+    let now = Clock::get()?.slot;
+    if let Some(tournament) = &mut ctx.accounts.tournament {
+        let points = ctx.accounts.profile.kills - kills;
+        tournament.record_score(&ctx.accounts.player.key(), points, now);
+    }
+
+    // 领取被击沉船主的悬赏
+    let sunk: Vec<Pubkey> = game
+        .sunk()
+        .iter()
+        .chain(neighbour.iter().flat_map(|other| other.sunk()))
+        .copied()
+        .collect();
+    collect_bounties(
+        ctx.remaining_accounts,
+        &sunk,
+        now,
+        &ctx.accounts.player,
+        &mut ctx.accounts.profile,
+    )?;
+
+    // 与附近的队友平分击杀奖励
+    if reward > 0 {
+        let teammates = game.nearby_teammates(ctx.accounts.player.key, neighbour.as_ref());
+        let share = reward / (teammates.len() as u64 + 1);
+        for key in teammates {
+            let teammate = accounts
+                .iter()
+                .find(|account| *account.key == key)
+                .ok_or(SevenSeasError::MissingTeammateAccount)?;
+            let cpi_context = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.player.to_account_info(),
+                    to: teammate.clone(),
+                },
+            );
+            anchor_lang::system_program::transfer(cpi_context, share)?;
+            msg!("Shared {} lamports with {}", share, teammate.key);
         }
     }
-    game.print();
     Ok(())
 }
 
+/// 领取传入的悬赏账户中目标已被击沉的悬赏
+///
+/// # 参数
+/// * `accounts` - 指令的 remaining_accounts,其中不是悬赏的账户会被跳过
+/// * `sunk` - 本次指令中被击杀者击沉的船主
+/// * `now` - 当前slot
+/// * `recipient` - 接收悬赏SOL的账户
+/// * `profile` - 击杀者的玩家档案,记入悬赏金币
+///
+/// # 返回值
+/// * `Result<u64>` - 领取的SOL总额
+pub(crate) fn collect_bounties(
+    accounts: &[AccountInfo],
+    sunk: &[Pubkey],
+    now: u64,
+    recipient: &AccountInfo,
+    profile: &mut PlayerProfile,
+) -> Result<u64> {
+    let mut lamports = 0;
+    if sunk.is_empty() {
+        return Ok(lamports);
+    }
+    for account in accounts
+        .iter()
+        .filter(|account| *account.owner == crate::ID)
+    {
+        lamports += collect_bounty(account, sunk, now, recipient, profile)?;
+    }
+    Ok(lamports)
+}
+
+// 领取悬赏,SOL转给接收者,金币记入击杀者的档案,返回领取的SOL
+//
+// 账户不是悬赏、目标没有在本次指令中被击沉或悬赏已过期时不做任何事
+fn collect_bounty(
+    account: &AccountInfo,
+    sunk: &[Pubkey],
+    now: u64,
+    recipient: &AccountInfo,
+    profile: &mut PlayerProfile,
+) -> Result<u64> {
+    let mut bounty = {
+        let data = account.try_borrow_data()?;
+        if !data.starts_with(&Bounty::discriminator()) {
+            return Ok(0);
+        }
+        Bounty::try_deserialize(&mut &data[..])?
+    };
+    if !sunk.contains(&bounty.target) || !bounty.is_active(now) {
+        return Ok(0);
+    }
+
+    let lamports = bounty.lamports;
+    **account.try_borrow_mut_lamports()? -= lamports;
+    **recipient.try_borrow_mut_lamports()? += lamports;
+    profile.accrue_gold(bounty.gold);
+    msg!(
+        "Collected bounty on {}: {} lamports and {} gold",
+        bounty.target,
+        lamports,
+        bounty.gold
+    );
+
+    bounty.lamports = 0;
+    bounty.gold = 0;
+    bounty.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    Ok(lamports)
+}
+
+// 销毁一个弹药代币
+fn consume_ammo(ctx: &Context<Shoot>, ammo: u8) -> Result<()> {
+    let mint = ctx
+        .accounts
+        .mint_registry
+        .ammo_mint(ammo)
+        .ok_or(SevenSeasError::InvalidAmmoType)?;
+    let (Some(ammo_mint), Some(ammo_account), Some(token_program)) = (
+        &ctx.accounts.ammo_mint,
+        &ctx.accounts.player_ammo_account,
+        &ctx.accounts.token_program,
+    ) else {
+        return Err(SevenSeasError::MissingAmmoAccounts.into());
+    };
+    if ammo_mint.key() != mint || ammo_account.mint != mint {
+        return Err(SevenSeasError::InvalidMint.into());
+    }
+    if ammo_account.owner != ctx.accounts.player.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+
+    let cpi_ctx = CpiContext::new(
+        token_program.to_account_info(),
+        Burn {
+            mint: ammo_mint.to_account_info(),
+            from: ammo_account.to_account_info(),
+            authority: ctx.accounts.player.to_account_info(),
+        },
+    );
+    anchor_spl::token::burn(cpi_ctx, to_base_units(1, ammo_mint.decimals))
+}
+
 #[derive(Accounts)]
 pub struct Shoot<'info> {
-    /// CHECK:
+    /// 宝箱金库账户,击沉船只的奖励从这里发放
     #[account(
         mut,
         seeds = [b"chestVault"],
-        bump
+        bump,
+        constraint = chest_vault.version == CHEST_VAULT_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
-    pub chest_vault: AccountInfo<'info>,
+    pub chest_vault: Account<'info, ChestVaultAccount>,
     #[account(mut)]
     pub game_data_account: AccountLoader<'info, GameDataAccount>,
+    /// 相邻区块的游戏数据账户,炮弹越过区块边缘时命中其中的船只
     #[account(mut)]
+    pub neighbour_game_data_account: Option<AccountLoader<'info, GameDataAccount>>,
+    #[account(
+        mut,
+        seeds = [b"gameActions_history"],
+        bump,
+        constraint = game_actions.version() == GAME_ACTIONS_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
     pub game_actions: Account<'info, GameActionHistory>,
     #[account(mut)]
     pub player: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    /// CHECK:
-    pub token_account_owner: AccountInfo<'info>,
-    #[account(      
-        init_if_needed,
-        payer = player,
-        associated_token::mint = mint_of_token_being_sent,
-        associated_token::authority = token_account_owner      
-    )]
-    pub player_token_account: Account<'info, TokenAccount>,
+    /// 玩家档案,记入移动和射击获得的金币
     #[account(
         mut,
-        seeds=[b"token_vault".as_ref(), mint_of_token_being_sent.key().as_ref()],
-        token::mint=mint_of_token_being_sent,
-        token::authority=token_account_owner_pda,
-        bump
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        constraint = profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
-    pub vault_token_account: Account<'info, TokenAccount>,
-    /// CHECK:
+    pub config: Account<'info, GameConfig>,
+
+    /// 铸币注册表,用于查找弹药对应的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 弹药铸币账户,使用普通炮弹时不需要
+    #[account(mut)]
+    pub ammo_mint: Option<Account<'info, Mint>>,
+
+    /// 玩家的弹药代币账户,使用普通炮弹时不需要
+    #[account(mut)]
+    pub player_ammo_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Option<Program<'info, Token>>,
+
+    /// 用于与队友分享击杀奖励
+    pub system_program: Program<'info, System>,
+
+    /// 锦标赛账户,传入时比赛期间击沉船只计分
     #[account(
         mut,
-        seeds=[b"token_account_owner_pda".as_ref()],
-        bump
+        constraint = tournament.version == TOURNAMENT_VERSION @ SevenSeasError::AccountNeedsMigration,
+        constraint = tournament.game_data_account == game_data_account.key() @ SevenSeasError::InvalidTournament
     )]
-    pub token_account_owner_pda: AccountInfo<'info>,
-    pub mint_of_token_being_sent: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub tournament: Option<Account<'info, Tournament>>,
 }
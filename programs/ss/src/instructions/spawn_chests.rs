@@ -0,0 +1,88 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    ChestVaultAccount, GameBoard, GameConfig, GameDataAccount, LootTable, TreasuryAccount,
+    CHEST_REWARD, CHEST_VAULT_VERSION, GAME_CONFIG_VERSION, LOOT_TABLE_VERSION, PAUSE_SPAWN_CHESTS,
+    TREASURY_VERSION,
+};
+use anchor_lang::prelude::*;
+
+/// 单次调用最多生成的宝箱数量,限制计算量
+pub const MAX_CHESTS_PER_CRANK: u64 = 8;
+
+/// 补充宝箱,由keeper定时调用
+///
+/// # 功能说明
+/// 1. 按棋盘的宝箱密度和最多宝箱数计算还缺多少个宝箱,单次最多生成 MAX_CHESTS_PER_CRANK 个
+/// 2. 每个宝箱的奖励从宝箱国库转入宝箱金库,国库保留租金豁免所需的余额
+/// 3. 国库余额不足以支付任何一个缺少的宝箱时返回 TreasuryEmpty
+pub fn spawn_chests(ctx: Context<SpawnChests>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_SPAWN_CHESTS)?;
+    let mut game = GameBoard::load_mut(&ctx.accounts.game_data_account)?;
+    let missing = game.missing_chests().min(MAX_CHESTS_PER_CRANK);
+    if missing == 0 {
+        msg!("Board already has enough chests");
+        return Ok(());
+    }
+
+    let treasury = ctx.accounts.treasury.to_account_info();
+    let chest_vault = ctx.accounts.chest_vault.to_account_info();
+    let reserve = Rent::get()?.minimum_balance(treasury.data_len());
+
+    let mut spawned = 0;
+    while spawned < missing && treasury.lamports() >= reserve + CHEST_REWARD {
+        game.spawn_chest(ctx.accounts.loot_table.as_deref())?;
+        **treasury.try_borrow_mut_lamports()? -= CHEST_REWARD;
+        **chest_vault.try_borrow_mut_lamports()? += CHEST_REWARD;
+        spawned += 1;
+    }
+    if spawned == 0 {
+        return Err(SevenSeasError::TreasuryEmpty.into());
+    }
+
+    msg!("Spawned {} of {} missing chests", spawned, missing);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SpawnChests<'info> {
+    /// 调用者,任何人都可以调用
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 宝箱金库账户,存放新宝箱的奖励
+    #[account(
+        mut,
+        seeds = [b"chestVault"],
+        bump,
+        constraint = chest_vault.version == CHEST_VAULT_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub chest_vault: Account<'info, ChestVaultAccount>,
+
+    /// 宝箱国库,为新宝箱支付奖励
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump,
+        constraint = treasury.version == TREASURY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub treasury: Account<'info, TreasuryAccount>,
+
+    /// 宝箱掉落表,用于选择新宝箱的等级,没有时生成普通宝箱
+    #[account(
+        seeds = [b"loot_table"],
+        bump,
+        constraint = loot_table.version == LOOT_TABLE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub loot_table: Option<Account<'info, LootTable>>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+}
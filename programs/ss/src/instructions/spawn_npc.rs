@@ -0,0 +1,64 @@
+pub use crate::errors::SevenSeasError;
+use crate::{
+    ChestVaultAccount, GameBoard, GameDataAccount, TreasuryAccount, CHEST_VAULT_VERSION,
+    NPC_BOUNTY, TREASURY_VERSION,
+};
+use anchor_lang::prelude::*;
+
+/// 生成NPC海盗船(仅棋盘管理员)
+///
+/// # 参数
+/// * `behaviour` - NPC行为,见 NPC_* 常量
+/// * `class` - 船型,见 SHIP_CLASSES
+///
+/// # 功能说明
+/// NPC的击沉赏金从宝箱国库转入宝箱金库,国库保留租金豁免所需的余额
+pub fn spawn_npc(ctx: Context<SpawnNpc>, behaviour: u8, class: u8) -> Result<()> {
+    if ctx.accounts.game_data_account.load()?.authority() != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+
+    let treasury = ctx.accounts.treasury.to_account_info();
+    let reserve = Rent::get()?.minimum_balance(treasury.data_len());
+    if treasury.lamports() < reserve + NPC_BOUNTY {
+        return Err(SevenSeasError::TreasuryEmpty.into());
+    }
+
+    GameBoard::load_mut(&ctx.accounts.game_data_account)?.spawn_npc(behaviour, class)?;
+
+    **treasury.try_borrow_mut_lamports()? -= NPC_BOUNTY;
+    **ctx
+        .accounts
+        .chest_vault
+        .to_account_info()
+        .try_borrow_mut_lamports()? += NPC_BOUNTY;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SpawnNpc<'info> {
+    /// 棋盘管理员
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+
+    /// 宝箱金库账户,存放NPC的击沉赏金
+    #[account(
+        mut,
+        seeds = [b"chestVault"],
+        bump,
+        constraint = chest_vault.version == CHEST_VAULT_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub chest_vault: Account<'info, ChestVaultAccount>,
+
+    /// 宝箱国库,支付NPC的击沉赏金
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump,
+        constraint = treasury.version == TREASURY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub treasury: Account<'info, TreasuryAccount>,
+}
@@ -1,6 +1,10 @@
 pub use crate::errors::SevenSeasError;
+use crate::instructions::salvage_ship::sink_ship_cargo;
 use crate::{
-    ChestVaultAccount, GameDataAccount, Ship, CHEST_REWARD, PLAYER_KILL_REWARD, PLAY_GAME_FEE,
+    from_base_units, ChestVaultAccount, GameBoard, GameConfig, GameDataAccount, LootTable,
+    MintRegistry, PlayerProfile, Ship, CHEST_REWARD, CHEST_VAULT_VERSION, GAME_CONFIG_VERSION,
+    LOOT_TABLE_VERSION, MINT_REGISTRY_VERSION, PAUSE_SPAWN_PLAYER, PLAYER_KILL_REWARD,
+    PLAYER_PROFILE_VERSION, PLAY_GAME_FEE, SHIP_VERSION,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::{
@@ -18,95 +22,143 @@ use anchor_spl::{
 /// * `Result<()>` - 成功返回Ok(()),失败返回错误
 ///
 /// # 功能说明
-/// 1. 计算玩家拥有的大炮数量和额外生命值
-/// 2. 生成玩家并转移游戏费用到宝箱账户
-/// 3. 生成宝箱并转移宝箱奖励到宝箱账户
+/// 1. 船只上次出海后没有返航,说明已被击沉,先销毁一部分船载代币并记录一次死亡
+/// 2. 根据船载金库计算大炮数量和额外生命值
+/// 3. 生成玩家并转移游戏费用到宝箱账户,团队模式中以玩家所属的公会作为队伍
+/// 4. 生成宝箱并转移宝箱奖励到宝箱账户,宝箱由 spawn_chests 维持时跳过
 pub fn spawn_player(ctx: Context<SpawnPlayer>, avatar: Pubkey) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_SPAWN_PLAYER)?;
+
+    // 第一次出海时创建玩家档案
+    let profile = &mut ctx.accounts.profile;
+    if profile.version == 0 {
+        profile.version = PLAYER_PROFILE_VERSION;
+        profile.owner = ctx.accounts.player.key();
+    }
+    profile.selected_ship = ctx.accounts.ship.key();
+
+    // 结算被击沉船只的损失,棋盘重置时被移除的船只没有损失
+    let epoch = ctx.accounts.game_data_account.load()?.epoch();
+    if ctx.accounts.ship.deployed && ctx.accounts.ship.board_epoch == epoch {
+        profile.record_death();
+        sink_ship_cargo(
+            ctx.accounts.ship.cannons,
+            (&ctx.accounts.ship_cannon_vault, &ctx.accounts.cannon_mint),
+            (&ctx.accounts.ship_rum_vault, &ctx.accounts.rum_mint),
+            ctx.accounts.token_account_owner_pda.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.bumps.token_account_owner_pda,
+        )?;
+        ctx.accounts.ship_cannon_vault.reload()?;
+        ctx.accounts.ship_rum_vault.reload()?;
+    }
+
     // 获取游戏数据账户和船只账户的可变引用,用于后续修改
-    let mut game = ctx.accounts.game_data_account.load_mut()?;
+    let mut game = GameBoard::load_mut(&ctx.accounts.game_data_account)?;
     let ship = &mut ctx.accounts.ship;
-
-    // 计算玩家拥有的大炮数量
-    // 根据代币精度转换:amount / (10^decimals)
-    let decimals = ctx.accounts.cannon_mint.decimals;
-    ship.cannons = ctx.accounts.cannon_token_account.amount / 10u64.pow(decimals as u32);
+    ship.deployed = true;
+    ship.board = ctx.accounts.game_data_account.key();
+    ship.board_epoch = epoch;
+
+    // 计算船载的大炮数量,不超过船型的炮位数量
+    // 根据大炮代币精度转换:amount / (10^decimals)
+    let cannon_slots = ship
+        .class_stats()
+        .ok_or(SevenSeasError::InvalidShipClass)?
+        .cannon_slots;
+    ship.cannons = from_base_units(
+        ctx.accounts.ship_cannon_vault.amount,
+        ctx.accounts.cannon_mint.decimals,
+    )
+    .min(cannon_slots);
 
     // 计算额外生命值
-    // 根据朗姆酒代币数量:amount / (10^decimals)
-    let extra_health = ctx.accounts.rum_token_account.amount / 10u64.pow(decimals as u32);
+    // 根据船载朗姆酒代币精度转换:amount / (10^decimals)
+    let extra_health = from_base_units(
+        ctx.accounts.ship_rum_vault.amount,
+        ctx.accounts.rum_mint.decimals,
+    );
 
     msg!("Spawned player! With {} cannons", ship.cannons);
 
     // 生成玩家,并转移游戏费用到宝箱账户
     // 游戏费用 = 击杀奖励 + 游戏费用
-    match game.spawn_player(ctx.accounts.player.to_account_info(), avatar, ship, extra_health) {
-        Ok(_) => {
-            // 创建CPI上下文,用于转移SOL
-            let cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.token_account_owner.to_account_info(),
-                    to: ctx.accounts.chest_vault.to_account_info(),
-                },
-            );
-            /*
-            加 ?: 如果转账失败，会直接返回错误并退出当前函数
-            不加 ?: 需要手动处理成功和失败的情况
-            在这个场景中，我们希望转账失败时直接返回错误并终止操作，所以使用 ? 是更好的选择。
-            这也符合 Solana 程序的错误处理模式，让错误能够正确地传播给客户端。
-
-             */
-            // 转移游戏费用到宝箱账户
-            anchor_lang::system_program::transfer(
-                cpi_context,
-                PLAYER_KILL_REWARD + PLAY_GAME_FEE,
-            )?;
-        }
-        Err(err) => {
-            return Err(err.into());
-        }
+    game.spawn_player(
+        ctx.accounts.player.to_account_info(),
+        avatar,
+        ship,
+        extra_health,
+        profile.guild,
+    )?;
+    // 创建CPI上下文,用于转移SOL
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.token_account_owner.to_account_info(),
+            to: ctx.accounts.chest_vault.to_account_info(),
+        },
+    );
+    /*
+    加 ?: 如果转账失败，会直接返回错误并退出当前函数
+    不加 ?: 需要手动处理成功和失败的情况
+    在这个场景中，我们希望转账失败时直接返回错误并终止操作，所以使用 ? 是更好的选择。
+    这也符合 Solana 程序的错误处理模式，让错误能够正确地传播给客户端。
+
+     */
+    // 转移游戏费用到宝箱账户
+    anchor_lang::system_program::transfer(cpi_context, PLAYER_KILL_REWARD + PLAY_GAME_FEE)?;
+
+    // 宝箱由 spawn_chests 维持时,不再附带生成宝箱
+    if game.chest_spawning_enabled() {
+        return Ok(());
     }
 
     // 生成宝箱,并转移宝箱奖励到宝箱账户
-    match game.spawn_chest(ctx.accounts.player.to_account_info()) {
-        Ok(_a) => {
-            // 创建CPI上下文,用于转移SOL
-            let cpi_context = CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.token_account_owner.to_account_info(),
-                    to: ctx.accounts.chest_vault.to_account_info(),
-                },
-            );
-            // 转移宝箱奖励到宝箱账户
-            anchor_lang::system_program::transfer(cpi_context, CHEST_REWARD)?;
-        }
-        Err(err) => {
-            return Err(err.into());
-        }
-    }
+    game.spawn_chest(ctx.accounts.loot_table.as_deref())?;
+    // 创建CPI上下文,用于转移SOL
+    let cpi_context = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        anchor_lang::system_program::Transfer {
+            from: ctx.accounts.token_account_owner.to_account_info(),
+            to: ctx.accounts.chest_vault.to_account_info(),
+        },
+    );
+    // 转移宝箱奖励到宝箱账户
+    anchor_lang::system_program::transfer(cpi_context, CHEST_REWARD)?;
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct SpawnPlayer<'info> {
-    /// CHECK: 这里等于player EOA
+    /// 玩家EOA账户,需要签名
     #[account(mut)]
-    pub player: AccountInfo<'info>,
+    pub player: Signer<'info>,
 
     /// 代币账户所有者,这里是玩家EOA账户,需要签名
     /// 用于支付游戏费用和宝箱奖励
-    #[account(mut)]
+    #[account(mut, constraint = token_account_owner.key() == player.key() @ SevenSeasError::Unauthorized)]
     pub token_account_owner: Signer<'info>,
 
     /// 宝箱金库账户,用于存储游戏费用和奖励
     #[account(
         mut,
         seeds = [b"chestVault"],
-        bump
+        bump,
+        constraint = chest_vault.version == CHEST_VAULT_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
     pub chest_vault: Account<'info, ChestVaultAccount>,
 
+    /// 玩家档案,记入移动和射击获得的金币以及终身统计
+    /// 如果不存在则创建
+    #[account(
+        init_if_needed,
+        payer = token_account_owner,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        space = 8 + PlayerProfile::INIT_SPACE
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
     /// 游戏数据账户,存储游戏状态
     /// 包含所有玩家和宝箱的位置信息
     #[account(mut)]
@@ -114,44 +166,86 @@ pub struct SpawnPlayer<'info> {
 
     /// 船只账户,存储玩家船只信息
     /// 通过PDA派生,种子为"ship"和NFT账户公钥
+    /// 已出海的船只只能在所在的棋盘上重新出海,不在棋盘上才会结算击沉的损失
     #[account(
         mut,
         seeds = [b"ship", nft_account.key().as_ref()],
-        bump
+        bump,
+        constraint = ship.version == SHIP_VERSION @ SevenSeasError::AccountNeedsMigration,
+        constraint = !ship.deployed || ship.board == game_data_account.key() @ SevenSeasError::WrongBoard
     )]
     pub ship: Account<'info, Ship>,
 
     /// NFT账户,这里是玩家EOA账户,后续改为代币账户
     /// 用于标识玩家拥有的船只
     /// CHECK: change to token account later
+    #[account(constraint = nft_account.key() == player.key() @ SevenSeasError::Unauthorized)]
     pub nft_account: AccountInfo<'info>,
 
-    /// 玩家大炮代币账户
-    #[account(      
+    /// 船载大炮金库,通过equip_cannons装备
+    /// 如果不存在则创建,此时船只没有装备大炮
+    #[account(
         init_if_needed,
         payer = token_account_owner,
-        associated_token::mint = cannon_mint,
-        associated_token::authority = token_account_owner      
+        seeds = [b"ship_cannons", ship.key().as_ref()],
+        bump,
+        token::mint = cannon_mint,
+        token::authority = token_account_owner_pda
     )]
-    pub cannon_token_account: Account<'info, TokenAccount>,
+    pub ship_cannon_vault: Account<'info, TokenAccount>,
 
-    /// 大炮代币铸币账户
+    /// 大炮代币铸币账户,销毁被击沉船只的大炮时需要可写
+    #[account(mut, address = mint_registry.cannon_mint @ SevenSeasError::InvalidMint)]
     pub cannon_mint: Account<'info, Mint>,
 
-    /// 玩家朗姆酒代币账户
-    /// 如果不存在则创建,用于存储玩家的朗姆酒代币
-    #[account(      
+    /// 船载朗姆酒金库,通过stock_rum补充
+    /// 如果不存在则创建,此时船只没有朗姆酒
+    #[account(
         init_if_needed,
         payer = token_account_owner,
-        associated_token::mint = rum_mint,
-        associated_token::authority = token_account_owner      
+        seeds = [b"ship_rum", ship.key().as_ref()],
+        bump,
+        token::mint = rum_mint,
+        token::authority = token_account_owner_pda
     )]
-    pub rum_token_account: Account<'info, TokenAccount>,
+    pub ship_rum_vault: Account<'info, TokenAccount>,
 
-    /// 朗姆酒代币铸币账户
+    /// 朗姆酒代币铸币账户,销毁被击沉船只的朗姆酒时需要可写
+    #[account(mut, address = mint_registry.rum_mint @ SevenSeasError::InvalidMint)]
     pub rum_mint: Account<'info, Mint>,
 
+    /// CHECK: 船载金库的所有者PDA
+    #[account(
+        seeds = [b"token_account_owner_pda".as_ref()],
+        bump
+    )]
+    pub token_account_owner_pda: AccountInfo<'info>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
+
+    /// 宝箱掉落表,用于选择新宝箱的等级,没有时生成普通宝箱
+    #[account(
+        seeds = [b"loot_table"],
+        bump,
+        constraint = loot_table.version == LOOT_TABLE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub loot_table: Option<Account<'info, LootTable>>,
 }
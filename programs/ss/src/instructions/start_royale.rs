@@ -0,0 +1,27 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameBoard, GameDataAccount};
+use anchor_lang::prelude::*;
+
+/// 开始大逃杀(仅棋盘管理员)
+///
+/// # 参数
+/// * `storm_interval_slots` - 风暴两次收缩之间的最少slot数
+///
+/// # 功能说明
+/// 开始后关闭 spawn_player,风暴由 storm_tick 定时收缩
+pub fn start_royale(ctx: Context<StartRoyale>, storm_interval_slots: u64) -> Result<()> {
+    if ctx.accounts.game_data_account.load()?.authority() != ctx.accounts.signer.key() {
+        return Err(SevenSeasError::Unauthorized.into());
+    }
+    GameBoard::load_mut(&ctx.accounts.game_data_account)?.start_royale(storm_interval_slots)
+}
+
+#[derive(Accounts)]
+pub struct StartRoyale<'info> {
+    /// 棋盘管理员
+    pub signer: Signer<'info>,
+
+    /// 游戏数据账户
+    #[account(mut)]
+    pub game_data_account: AccountLoader<'info, GameDataAccount>,
+}
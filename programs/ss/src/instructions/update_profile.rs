@@ -0,0 +1,42 @@
+pub use crate::errors::SevenSeasError;
+use crate::{PlayerProfile, PLAYER_PROFILE_VERSION};
+use anchor_lang::prelude::*;
+
+/// 设置玩家档案的显示名称和头像
+///
+/// # 参数
+/// * `ctx` - 指令上下文
+/// * `name` - 显示名称,不超过 PROFILE_NAME_LEN 字节
+/// * `avatar` - 偏好的头像公钥
+///
+/// # 功能说明
+/// 档案不存在时创建,之后出海时也会使用同一个档案
+pub fn update_profile(ctx: Context<UpdateProfile>, name: String, avatar: Pubkey) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    if profile.version == 0 {
+        profile.version = PLAYER_PROFILE_VERSION;
+        profile.owner = ctx.accounts.player.key();
+    }
+    profile.set_name(&name)?;
+    profile.avatar = avatar;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateProfile<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    /// 玩家档案,如果不存在则创建
+    #[account(
+        init_if_needed,
+        payer = player,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        constraint = profile.version == 0 || profile.version == PLAYER_PROFILE_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
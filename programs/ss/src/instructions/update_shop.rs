@@ -0,0 +1,50 @@
+pub use crate::errors::SevenSeasError;
+use crate::{GameConfig, Shop, GAME_CONFIG_VERSION};
+use anchor_lang::prelude::*;
+
+/// 修改商店价格和每日限购(仅管理员)
+///
+/// # 参数
+/// * `cannon_price` - 每门大炮的价格(金币),为0时停止出售
+/// * `rum_price` - 每瓶朗姆酒的价格(金币),为0时停止出售
+/// * `daily_limit` - 每个钱包每天最多购买的商品数量
+pub fn update_shop(
+    ctx: Context<UpdateShop>,
+    cannon_price: u64,
+    rum_price: u64,
+    daily_limit: u64,
+) -> Result<()> {
+    let shop = &mut ctx.accounts.shop;
+    shop.cannon_price = cannon_price;
+    shop.rum_price = rum_price;
+    shop.daily_limit = daily_limit;
+    msg!(
+        "Shop prices set to {} / {}, daily limit {}",
+        cannon_price,
+        rum_price,
+        daily_limit
+    );
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateShop<'info> {
+    pub authority: Signer<'info>,
+
+    // 游戏配置账户,只有管理员可以修改商店
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = authority @ SevenSeasError::Unauthorized,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    // 商店账户
+    #[account(
+        mut,
+        seeds = [b"shop"],
+        bump
+    )]
+    pub shop: Account<'info, Shop>,
+}
@@ -4,10 +4,14 @@ use anchor_lang::prelude::*;
 use anchor_lang::prelude::Account;
 use anchor_spl::token::{Mint, Token, TokenAccount, Transfer};
 
-use crate::{Ship, TOKEN_DECIMAL_MULTIPLIER};
+use crate::{
+    to_base_units, GameConfig, MintRegistry, Ship, GAME_CONFIG_VERSION, MINT_REGISTRY_VERSION,
+    PAUSE_UPGRADE_SHIP, SHIP_UPGRADE_HEALTH_BONUS, SHIP_VERSION,
+};
 
 /// 升级船只
 pub fn upgrade_ship(ctx: Context<UpgradeShip>) -> Result<()> {
+    ctx.accounts.config.ensure_not_paused(PAUSE_UPGRADE_SHIP)?;
     // 创建代币转账指令
     let transfer_instruction = Transfer {
         from: ctx.accounts.player_token_account.to_account_info(),
@@ -21,35 +25,35 @@ pub fn upgrade_ship(ctx: Context<UpgradeShip>) -> Result<()> {
         transfer_instruction,
     );
 
-    // 根据升级次数确定升级费用和新属性
-    let cost: u64;
-    match ctx.accounts.new_ship.upgrades {
-        0 => {
-            ctx.accounts.new_ship.health = 100;
-            ctx.accounts.new_ship.upgrades = 1;
-            cost = 5; // 5代币
-        }
-        1 => {
-            ctx.accounts.new_ship.health = 150;
-            ctx.accounts.new_ship.upgrades = 2;
-            cost = 200; // 200代币
-        }
-        2 => {
-            ctx.accounts.new_ship.health = 300;
-            ctx.accounts.new_ship.upgrades = 3;
-            cost = 1500; // 1500代币
-        }
-        3 => {
-            ctx.accounts.new_ship.health = 500;
-            ctx.accounts.new_ship.upgrades = 4;
-            cost = 25000; // 25000代币
-        }
+    // 每种船型有各自的升级上限
+    let stats = ctx
+        .accounts
+        .new_ship
+        .class_stats()
+        .ok_or(SevenSeasError::InvalidShipClass)?;
+    if ctx.accounts.new_ship.upgrades >= stats.max_upgrades {
+        return Err(SevenSeasError::MaxShipLevelReached.into());
+    }
+
+    // 根据升级次数确定升级费用
+    let cost: u64 = match ctx.accounts.new_ship.upgrades {
+        0 => 5,     // 5代币
+        1 => 200,   // 200代币
+        2 => 1500,  // 1500代币
+        3 => 25000, // 25000代币
         _ => {
             return Err(SevenSeasError::MaxShipLevelReached.into());
         }
-    }
-    // 执行代币转账,乘以小数位数
-    anchor_spl::token::transfer(cpi_ctx, cost * TOKEN_DECIMAL_MULTIPLIER);
+    };
+    // 升级后的生命值为船型基础生命值加上升级奖励
+    let upgrades = ctx.accounts.new_ship.upgrades + 1;
+    ctx.accounts.new_ship.upgrades = upgrades;
+    ctx.accounts.new_ship.health = stats.base_health + SHIP_UPGRADE_HEALTH_BONUS[upgrades as usize];
+    // 执行代币转账,按金币精度换算
+    anchor_spl::token::transfer(
+        cpi_ctx,
+        to_base_units(cost, ctx.accounts.mint_of_token_being_sent.decimals),
+    )?;
 
     msg!("Ship upgraded to level: {}", ctx.accounts.new_ship.upgrades);
 
@@ -64,7 +68,8 @@ pub struct UpgradeShip<'info> {
     /// 船只账户
     #[account(
         seeds = [b"ship", nft_account.key().as_ref()],
-        bump
+        bump,
+        constraint = new_ship.version == SHIP_VERSION @ SevenSeasError::AccountNeedsMigration
     )]
     #[account(mut)]
     pub new_ship: Account<'info, Ship>,
@@ -88,6 +93,23 @@ pub struct UpgradeShip<'info> {
         bump
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(address = mint_registry.gold_mint @ SevenSeasError::InvalidMint)]
     pub mint_of_token_being_sent: Account<'info, Mint>,
     pub token_program: Program<'info, Token>,
+
+    /// 游戏配置账户,用于检查指令是否被暂停
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.version == GAME_CONFIG_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    /// 铸币注册表,用于校验传入的铸币账户
+    #[account(
+        seeds = [b"mint_registry"],
+        bump,
+        constraint = mint_registry.version == MINT_REGISTRY_VERSION @ SevenSeasError::AccountNeedsMigration
+    )]
+    pub mint_registry: Account<'info, MintRegistry>,
 }
@@ -37,14 +37,17 @@ pub mod ss {
         instructions::initialize_game_actions(ctx)
     }
 
-    /// 初始化游戏数据账户
-    pub fn initialize_game_data(ctx: Context<InitializeGameData>) -> Result<()> {
-        instructions::initialize_game_data(ctx)
+    /// 初始化游戏数据账户,可选地根据种子生成棋盘
+    pub fn initialize_game_data(
+        ctx: Context<InitializeGameData>,
+        params: Option<BoardGenParams>,
+    ) -> Result<()> {
+        instructions::initialize_game_data(ctx, params)
     }
 
-    /// 初始化船只
-    pub fn initialize_ship(ctx: Context<InitializeShip>) -> Result<()> {
-        instructions::initialize_ship::initialize_ship(ctx)
+    /// 初始化指定船型的船只
+    pub fn initialize_ship(ctx: Context<InitializeShip>, class: u8) -> Result<()> {
+        instructions::initialize_ship::initialize_ship(ctx, class)
     }
 
     /// 升级船只
@@ -52,17 +55,222 @@ pub mod ss {
         instructions::upgrade_ship::upgrade_ship(ctx)
     }
 
-    //重置游戏
+    /// 初始化开放海域中的区块
+    pub fn initialize_chunk(
+        ctx: Context<InitializeChunk>,
+        chunk_x: u16,
+        chunk_y: u16,
+        params: Option<BoardGenParams>,
+    ) -> Result<()> {
+        instructions::initialize_chunk(ctx, chunk_x, chunk_y, params)
+    }
+
+    /// 扩容游戏数据账户(仅管理员)
+    pub fn resize_game_data(ctx: Context<ResizeGameData>, width: u16, height: u16) -> Result<()> {
+        instructions::resize_game_data(ctx, width, height)
+    }
+
+    /// 设置被击沉的船只留在残骸中的托管奖励比例(仅棋盘管理员)
+    pub fn set_wreck_percent(ctx: Context<SetWreckPercent>, percent: u8) -> Result<()> {
+        instructions::set_wreck_percent(ctx, percent)
+    }
+
+    /// 设置宝箱掉落表(仅管理员)
+    pub fn set_loot_table(
+        ctx: Context<SetLootTable>,
+        tiers: [ChestTierConfig; CHEST_TIER_COUNT],
+    ) -> Result<()> {
+        instructions::set_loot_table(ctx, tiers)
+    }
+
+    /// 设置由 spawn_chests 维持的宝箱密度(仅棋盘管理员)
+    pub fn set_chest_spawning(
+        ctx: Context<SetChestSpawning>,
+        chest_density: u16,
+        max_chests: u16,
+    ) -> Result<()> {
+        instructions::set_chest_spawning(ctx, chest_density, max_chests)
+    }
+
+    /// 开启或关闭团队模式(仅棋盘管理员)
+    pub fn set_team_mode(ctx: Context<SetTeamMode>, enabled: bool) -> Result<()> {
+        instructions::set_team_mode(ctx, enabled)
+    }
+
+    /// 初始化游戏配置账户
+    pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
+        instructions::initialize_config(ctx)
+    }
+
+    /// 设置暂停位图(仅管理员)
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u32) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    /// 登记特殊弹药的铸币账户(仅管理员)
+    pub fn set_ammo_mints(ctx: Context<SetAmmoMints>) -> Result<()> {
+        instructions::set_ammo_mints(ctx)
+    }
+
+    /// 设置射击和移动的冷却时间(仅管理员)
+    pub fn set_cooldowns(
+        ctx: Context<SetCooldowns>,
+        shoot_cooldown_slots: u64,
+        move_cooldown_slots: u64,
+    ) -> Result<()> {
+        instructions::set_cooldowns(ctx, shoot_cooldown_slots, move_cooldown_slots)
+    }
+
+    /// 初始化铸币注册表(仅管理员)
+    pub fn initialize_mint_registry(ctx: Context<InitializeMintRegistry>) -> Result<()> {
+        instructions::initialize_mint_registry(ctx)
+    }
+
+    /// 迁移旧布局的游戏数据账户
+    pub fn migrate_game_data(ctx: Context<MigrateGameData>) -> Result<()> {
+        instructions::migrate_game_data(ctx)
+    }
+
+    /// 迁移旧布局的船只账户
+    pub fn migrate_ship(ctx: Context<MigrateShip>) -> Result<()> {
+        instructions::migrate_ship(ctx)
+    }
+
+    /// 迁移旧布局的游戏动作历史账户
+    pub fn migrate_game_actions(ctx: Context<MigrateGameActions>) -> Result<()> {
+        instructions::migrate_game_actions(ctx)
+    }
+
+    /// 迁移旧布局的宝箱金库账户
+    pub fn migrate_chest_vault(ctx: Context<MigrateChestVault>) -> Result<()> {
+        instructions::migrate_chest_vault(ctx)
+    }
+
+    //重置游戏(仅管理员)
     pub fn reset(_ctx: Context<Reset>) -> Result<()> {
-        _ctx.accounts.game_data_account.load_mut()?.reset()
+        if _ctx.accounts.game_data_account.load()?.authority() != _ctx.accounts.signer.key() {
+            return Err(SevenSeasError::Unauthorized.into());
+        }
+        GameBoard::load_mut(&_ctx.accounts.game_data_account)?.reset()
     }
 
     //重置船只
     pub fn reset_ship(_ctx: Context<ResetShip>) -> Result<()> {
-        _ctx.accounts
-            .game_data_account
-            .load_mut()?
-            .reset_ship(_ctx.accounts.signer.key())
+        let on_board = GameBoard::load_mut(&_ctx.accounts.game_data_account)?
+            .reset_ship(_ctx.accounts.signer.key())?;
+        // 船只从棋盘上返航,下次出海时不会被视为已被击沉
+        if on_board {
+            _ctx.accounts.ship.deployed = false;
+            _ctx.accounts.ship.board = Pubkey::default();
+        }
+        Ok(())
+    }
+
+    /// 为船只装备大炮
+    pub fn equip_cannons(ctx: Context<EquipCannons>, amount: u64) -> Result<()> {
+        instructions::equip_cannons(ctx, amount)
+    }
+
+    /// 为船只补充朗姆酒
+    pub fn stock_rum(ctx: Context<StockRum>, amount: u64) -> Result<()> {
+        instructions::stock_rum(ctx, amount)
+    }
+
+    /// 打捞被击沉的船只
+    pub fn salvage_ship(ctx: Context<SalvageShip>) -> Result<()> {
+        instructions::salvage_ship(ctx)
+    }
+
+    /// 初始化商店(仅管理员)
+    pub fn initialize_shop(
+        ctx: Context<InitializeShop>,
+        cannon_price: u64,
+        rum_price: u64,
+        daily_limit: u64,
+    ) -> Result<()> {
+        instructions::initialize_shop(ctx, cannon_price, rum_price, daily_limit)
+    }
+
+    /// 修改商店价格和每日限购(仅管理员)
+    pub fn update_shop(
+        ctx: Context<UpdateShop>,
+        cannon_price: u64,
+        rum_price: u64,
+        daily_limit: u64,
+    ) -> Result<()> {
+        instructions::update_shop(ctx, cannon_price, rum_price, daily_limit)
+    }
+
+    /// 用金币购买大炮或朗姆酒
+    pub fn buy(ctx: Context<Buy>, item: u8, amount: u64) -> Result<()> {
+        instructions::buy(ctx, item, amount)
+    }
+
+    /// 领取移动和射击获得的金币
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::claim_rewards(ctx)
+    }
+
+    /// 领取从宝箱和残骸中收集的物品
+    pub fn claim_loot(ctx: Context<ClaimLoot>, item: u8) -> Result<()> {
+        instructions::claim_loot(ctx, item)
+    }
+
+    /// 设置玩家档案的显示名称和头像
+    pub fn update_profile(ctx: Context<UpdateProfile>, name: String, avatar: Pubkey) -> Result<()> {
+        instructions::update_profile(ctx, name, avatar)
+    }
+
+    /// 创建公会,创建者成为会长
+    pub fn create_guild(ctx: Context<CreateGuild>, id: u16, name: String) -> Result<()> {
+        instructions::create_guild(ctx, id, name)
+    }
+
+    /// 加入公会,需要会长签名
+    pub fn join_guild(ctx: Context<JoinGuild>) -> Result<()> {
+        instructions::join_guild(ctx)
+    }
+
+    /// 离开公会
+    pub fn leave_guild(ctx: Context<LeaveGuild>) -> Result<()> {
+        instructions::leave_guild(ctx)
+    }
+
+    /// 悬赏指定玩家的船只,击沉者在射击时领取
+    pub fn place_bounty(
+        ctx: Context<PlaceBounty>,
+        lamports: u64,
+        gold: u64,
+        duration_slots: u64,
+    ) -> Result<()> {
+        instructions::place_bounty(ctx, lamports, gold, duration_slots)
+    }
+
+    /// 取回过期或已被领取的悬赏
+    pub fn refund_bounty(ctx: Context<RefundBounty>) -> Result<()> {
+        instructions::refund_bounty(ctx)
+    }
+
+    /// 创建限时锦标赛(仅棋盘管理员)
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        id: u64,
+        start_slot: u64,
+        end_slot: u64,
+        entry_fee: u64,
+        payouts: [u16; TOURNAMENT_PRIZE_PLACES],
+    ) -> Result<()> {
+        instructions::create_tournament(ctx, id, start_slot, end_slot, entry_fee, payouts)
+    }
+
+    /// 报名参加锦标赛
+    pub fn join_tournament(ctx: Context<JoinTournament>) -> Result<()> {
+        instructions::join_tournament(ctx)
+    }
+
+    /// 结束锦标赛并按排名发放奖金
+    pub fn finalize_tournament(ctx: Context<FinalizeTournament>) -> Result<()> {
+        instructions::finalize_tournament(ctx)
     }
 
     /// 生成玩家
@@ -75,13 +283,63 @@ pub mod ss {
         instructions::cthulhu(ctx)
     }
 
-    /// 射击
-    pub fn shoot(ctx: Context<Shoot>) -> Result<()> {
-        instructions::shoot(ctx)
+    /// 使用指定弹药射击
+    pub fn shoot<'info>(ctx: Context<'_, '_, '_, 'info, Shoot<'info>>, ammo: u8) -> Result<()> {
+        instructions::shoot(ctx, ammo)
     }
 
     /// 移动玩家(V2)
     pub fn move_player_v2(ctx: Context<MovePlayer>, direction: u8) -> Result<()> {
         instructions::move_player_v2(ctx, direction)
     }
+
+    /// 用朗姆酒修理船只
+    pub fn repair(ctx: Context<Repair>, amount: u64) -> Result<()> {
+        instructions::repair(ctx, amount)
+    }
+
+    /// 向宝箱国库注资
+    pub fn fund_treasury(ctx: Context<FundTreasury>, amount: u64) -> Result<()> {
+        instructions::fund_treasury(ctx, amount)
+    }
+
+    /// 补充宝箱到目标密度,由keeper定时调用
+    pub fn spawn_chests(ctx: Context<SpawnChests>) -> Result<()> {
+        instructions::spawn_chests(ctx)
+    }
+
+    /// 生成NPC海盗船(仅棋盘管理员)
+    pub fn spawn_npc(ctx: Context<SpawnNpc>, behaviour: u8, class: u8) -> Result<()> {
+        instructions::spawn_npc(ctx, behaviour, class)
+    }
+
+    /// NPC行动一次,由keeper定时调用
+    pub fn npc_tick(ctx: Context<NpcTick>) -> Result<()> {
+        instructions::npc_tick(ctx)
+    }
+
+    /// 开放大逃杀报名(仅棋盘管理员)
+    pub fn open_royale(ctx: Context<OpenRoyale>) -> Result<()> {
+        instructions::open_royale(ctx)
+    }
+
+    /// 开始大逃杀,之后不能再出海(仅棋盘管理员)
+    pub fn start_royale(ctx: Context<StartRoyale>, storm_interval_slots: u64) -> Result<()> {
+        instructions::start_royale(ctx, storm_interval_slots)
+    }
+
+    /// 大逃杀风暴收缩一圈,由keeper定时调用
+    pub fn storm_tick(ctx: Context<StormTick>) -> Result<()> {
+        instructions::storm_tick(ctx)
+    }
+
+    /// 最后一艘船领取大逃杀奖池
+    pub fn claim_royale(ctx: Context<ClaimRoyale>) -> Result<()> {
+        instructions::claim_royale(ctx)
+    }
+
+    /// 轻量移动,只能移动到空格子
+    pub fn move_lite(ctx: Context<MoveLite>, direction: u8) -> Result<()> {
+        instructions::move_lite(ctx, direction)
+    }
 }
@@ -0,0 +1,22 @@
+// 弹药类型
+//
+// 普通炮弹不消耗代币,其余弹药每次射击消耗一个对应的代币,
+// 代币的铸币账户登记在铸币注册表中
+
+/// 普通炮弹
+pub const AMMO_ROUND_SHOT: u8 = 0;
+/// 链弹: 命中后目标在一段时间内不能移动,并降低目标的移动速度
+pub const AMMO_CHAIN_SHOT: u8 = 1;
+/// 霰弹: 射程为1时伤害更高
+pub const AMMO_GRAPESHOT: u8 = 2;
+/// 爆破弹: 在命中点周围3×3范围内造成伤害
+pub const AMMO_EXPLOSIVE_SHELL: u8 = 3;
+
+/// 链弹每次命中降低的移动速度,速度最低为1
+///
+/// 速度只缩短移动冷却时间,没有设置移动冷却时间时不起作用
+pub const CHAIN_SHOT_SPEED_PENALTY: u8 = 1;
+/// 链弹命中后目标不能移动的slot数,不受移动冷却时间设置的影响
+pub const CHAIN_SHOT_PIN_SLOTS: u64 = 4;
+/// 霰弹在射程为1时的伤害倍数
+pub const GRAPESHOT_CLOSE_MULTIPLIER: u64 = 2;
@@ -0,0 +1,225 @@
+// 棋盘地形生成
+//
+// 这里的代码同时在链上程序和客户端/模拟器中使用,
+// 只依赖种子和参数,因此同一组参数在任何地方都会生成完全相同的棋盘,
+// 客户端可以在种子被使用之前预览生成结果。
+use crate::XorShift64;
+use anchor_lang::prelude::*;
+
+/// 岛屿密度上限(百分比)
+pub const MAX_ISLAND_DENSITY: u8 = 30;
+/// 暗礁密度上限(百分比)
+pub const MAX_REEF_DENSITY: u8 = 30;
+/// 初始宝箱数量上限
+pub const MAX_INITIAL_CHESTS: u8 = 10;
+/// 港口数量上限
+pub const MAX_PORTS: u8 = 4;
+
+/// 棋盘生成参数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BoardGenParams {
+    /// 随机种子
+    pub seed: u64,
+    /// 岛屿密度(百分比, 0-MAX_ISLAND_DENSITY)
+    pub island_density: u8,
+    /// 暗礁密度(百分比, 0-MAX_REEF_DENSITY)
+    pub reef_density: u8,
+    /// 初始宝箱数量
+    pub chest_count: u8,
+    /// 港口数量(0-MAX_PORTS),港口建在靠近海域的岛屿上
+    pub port_count: u8,
+}
+
+/// 生成的格子类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeneratedTile {
+    /// 空海域
+    Water,
+    /// 岛屿: 不可通行,阻挡炮弹
+    Island,
+    /// 暗礁: 不可通行,炮弹可以越过
+    Reef,
+    /// 宝箱
+    Chest,
+    /// 港口: 不可通行,阻挡炮弹,停靠在旁边的船只可以随时修理
+    Port,
+}
+
+impl BoardGenParams {
+    /// 检查参数是否在允许范围内
+    pub fn is_valid(&self) -> bool {
+        self.island_density <= MAX_ISLAND_DENSITY
+            && self.reef_density <= MAX_REEF_DENSITY
+            && self.chest_count <= MAX_INITIAL_CHESTS
+            && self.port_count <= MAX_PORTS
+    }
+}
+
+/// 开放海域中区块使用的种子
+///
+/// 由世界种子和区块坐标派生,每个区块的地形不同,但同一个世界种子总是生成同样的世界
+pub fn chunk_seed(world_seed: u64, chunk_x: u16, chunk_y: u16) -> u64 {
+    let coordinates = ((chunk_x as u64) << 16) | chunk_y as u64;
+    let mut rng = XorShift64::new(world_seed ^ coordinates.wrapping_mul(0x2545_F491_4F6C_DD1D));
+    rng.next()
+}
+
+/// 根据种子生成棋盘
+///
+/// # 参数
+/// * `params` - 生成参数
+/// * `width` - 棋盘宽度
+/// * `height` - 棋盘高度
+///
+/// # 返回值
+/// * `Vec<GeneratedTile>` - 按 `x * height + y` 排列的格子
+///
+/// # 功能说明
+/// 1. 按岛屿密度放置岛屿,并让岛屿向相邻格子扩展成群岛
+/// 2. 岛屿周围的海域更容易出现暗礁,其余海域按四分之一的密度出现暗礁
+/// 3. 在剩余的空海域中随机放置宝箱
+/// 4. 把随机几个靠近海域的岛屿改为港口
+pub fn generate_board(params: &BoardGenParams, width: usize, height: usize) -> Vec<GeneratedTile> {
+    let mut tiles = vec![GeneratedTile::Water; width * height];
+    // 种子混入一个常量后作为初始状态,混合结果为0时由 XorShift64::new 换成非零状态
+    let mut rng = XorShift64::new(params.seed ^ 0x9E37_79B9_7F4A_7C15);
+    let index = |x: usize, y: usize| x * height + y;
+
+    // 1. 放置岛屿
+    for x in 0..width {
+        for y in 0..height {
+            if rng.next() % 100 < params.island_density as u64 {
+                tiles[index(x, y)] = GeneratedTile::Island;
+            }
+        }
+    }
+    // 岛屿向右和向下扩展,形成连在一起的群岛
+    for x in 0..width {
+        for y in 0..height {
+            if tiles[index(x, y)] != GeneratedTile::Island {
+                continue;
+            }
+            if x + 1 < width && rng.next() % 100 < params.island_density as u64 {
+                tiles[index(x + 1, y)] = GeneratedTile::Island;
+            }
+            if y + 1 < height && rng.next() % 100 < params.island_density as u64 {
+                tiles[index(x, y + 1)] = GeneratedTile::Island;
+            }
+        }
+    }
+
+    // 2. 放置暗礁
+    for x in 0..width {
+        for y in 0..height {
+            if tiles[index(x, y)] != GeneratedTile::Water {
+                continue;
+            }
+            let near_island = (x > 0 && tiles[index(x - 1, y)] == GeneratedTile::Island)
+                || (x + 1 < width && tiles[index(x + 1, y)] == GeneratedTile::Island)
+                || (y > 0 && tiles[index(x, y - 1)] == GeneratedTile::Island)
+                || (y + 1 < height && tiles[index(x, y + 1)] == GeneratedTile::Island);
+            let density = if near_island {
+                params.reef_density as u64
+            } else {
+                params.reef_density as u64 / 4
+            };
+            if rng.next() % 100 < density {
+                tiles[index(x, y)] = GeneratedTile::Reef;
+            }
+        }
+    }
+
+    // 3. 放置宝箱
+    let mut water: Vec<usize> = (0..tiles.len())
+        .filter(|i| tiles[*i] == GeneratedTile::Water)
+        .collect();
+    for _ in 0..params.chest_count {
+        if water.is_empty() {
+            break;
+        }
+        let pick = (rng.next() % water.len() as u64) as usize;
+        tiles[water.swap_remove(pick)] = GeneratedTile::Chest;
+    }
+
+    // 4. 放置港口,放在最后一步,不影响前面的地形
+    let mut coast: Vec<usize> = (0..tiles.len())
+        .filter(|i| {
+            let (x, y) = (*i / height, *i % height);
+            tiles[*i] == GeneratedTile::Island
+                && ((x > 0 && tiles[index(x - 1, y)] == GeneratedTile::Water)
+                    || (x + 1 < width && tiles[index(x + 1, y)] == GeneratedTile::Water)
+                    || (y > 0 && tiles[index(x, y - 1)] == GeneratedTile::Water)
+                    || (y + 1 < height && tiles[index(x, y + 1)] == GeneratedTile::Water))
+        })
+        .collect();
+    for _ in 0..params.port_count {
+        if coast.is_empty() {
+            break;
+        }
+        let pick = (rng.next() % coast.len() as u64) as usize;
+        tiles[coast.swap_remove(pick)] = GeneratedTile::Port;
+    }
+
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: usize = 64;
+
+    fn params(seed: u64, density: u8) -> BoardGenParams {
+        BoardGenParams {
+            seed,
+            island_density: density,
+            reef_density: density,
+            chest_count: MAX_INITIAL_CHESTS,
+            port_count: MAX_PORTS,
+        }
+    }
+
+    fn count(tiles: &[GeneratedTile], kind: GeneratedTile) -> usize {
+        tiles.iter().filter(|tile| **tile == kind).count()
+    }
+
+    #[test]
+    fn same_seed_generates_same_board() {
+        let params = params(42, 20);
+        let board = generate_board(&params, SIZE, SIZE);
+        assert_eq!(board, generate_board(&params, SIZE, SIZE));
+
+        let other = BoardGenParams { seed: 43, ..params };
+        assert_ne!(board, generate_board(&other, SIZE, SIZE));
+    }
+
+    #[test]
+    fn seed_cancelling_the_mixing_constant_still_generates_terrain() {
+        let tiles = generate_board(&params(0x9E37_79B9_7F4A_7C15, 10), SIZE, SIZE);
+        assert!(count(&tiles, GeneratedTile::Island) < tiles.len() / 2);
+        assert!(count(&tiles, GeneratedTile::Water) > tiles.len() / 2);
+    }
+
+    #[test]
+    fn density_stays_within_bounds() {
+        for seed in 0..20 {
+            for density in [0, 10, MAX_ISLAND_DENSITY] {
+                let tiles = generate_board(&params(seed, density), SIZE, SIZE);
+                let percent = |amount: usize| amount * 100 / tiles.len();
+                // 群岛扩展会让岛屿多于放置的密度,但不会超过两倍
+                let islands =
+                    count(&tiles, GeneratedTile::Island) + count(&tiles, GeneratedTile::Port);
+                assert!(percent(islands) <= 2 * density as usize);
+                assert!(percent(islands) >= density as usize / 2);
+                // 每个空海域格子出现暗礁的概率不超过暗礁密度
+                assert!(percent(count(&tiles, GeneratedTile::Reef)) <= density as usize);
+                assert_eq!(
+                    count(&tiles, GeneratedTile::Chest),
+                    MAX_INITIAL_CHESTS as usize
+                );
+                let ports = if density == 0 { 0 } else { MAX_PORTS as usize };
+                assert_eq!(count(&tiles, GeneratedTile::Port), ports);
+            }
+        }
+    }
+}
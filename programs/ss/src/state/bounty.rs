@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+/// 悬赏账户布局版本
+pub const BOUNTY_VERSION: u8 = 1;
+
+/// 悬赏,种子为"bounty"、目标公钥和悬赏人公钥
+///
+/// SOL托管在悬赏账户自身(租金之外的部分),金币托管在金币金库中。
+/// 在有效期内击沉目标船只的玩家在射击或接舷战时传入悬赏账户即可领取;
+/// 过期后仍未被领取的悬赏由悬赏人通过 refund_bounty 取回。
+/// 客户端可以按 target 字段筛选悬赏账户来标记被通缉的船只
+#[account]
+#[derive(InitSpace)]
+pub struct Bounty {
+    /// 布局版本
+    pub version: u8,
+    /// 悬赏人
+    pub placer: Pubkey,
+    /// 目标玩家的钱包
+    pub target: Pubkey,
+    /// 托管的SOL(lamports)
+    pub lamports: u64,
+    /// 托管的金币(整数代币)
+    pub gold: u64,
+    /// 过期的slot,之后不能再领取
+    pub expiry_slot: u64,
+}
+
+impl Bounty {
+    /// 悬赏是否还有未领取的奖励
+    pub fn is_funded(&self) -> bool {
+        self.lamports > 0 || self.gold > 0
+    }
+
+    /// 悬赏在指定slot是否可以领取
+    pub fn is_active(&self, now: u64) -> bool {
+        self.is_funded() && now < self.expiry_slot
+    }
+}
@@ -0,0 +1,92 @@
+use crate::errors::SevenSeasError;
+use anchor_lang::prelude::*;
+
+/// 配置账户布局版本
+pub const GAME_CONFIG_VERSION: u8 = 1;
+
+/// 暂停位: 生成玩家
+pub const PAUSE_SPAWN_PLAYER: u32 = 1 << 0;
+/// 暂停位: 移动
+pub const PAUSE_MOVE_PLAYER: u32 = 1 << 1;
+/// 暂停位: 射击
+pub const PAUSE_SHOOT: u32 = 1 << 2;
+/// 暂停位: 克苏鲁攻击
+pub const PAUSE_CTHULHU: u32 = 1 << 3;
+/// 暂停位: 升级船只
+pub const PAUSE_UPGRADE_SHIP: u32 = 1 << 4;
+/// 暂停位: 修理船只
+pub const PAUSE_REPAIR: u32 = 1 << 5;
+/// 暂停位: 补充宝箱
+pub const PAUSE_SPAWN_CHESTS: u32 = 1 << 6;
+/// 暂停位: NPC行动
+pub const PAUSE_NPC_TICK: u32 = 1 << 7;
+/// 暂停位: 发布悬赏
+pub const PAUSE_PLACE_BOUNTY: u32 = 1 << 8;
+/// 暂停位: 报名锦标赛
+pub const PAUSE_JOIN_TOURNAMENT: u32 = 1 << 9;
+/// 暂停位: 大逃杀风暴
+pub const PAUSE_STORM_TICK: u32 = 1 << 10;
+/// 暂停位: 领取大逃杀奖池
+pub const PAUSE_CLAIM_ROYALE: u32 = 1 << 11;
+/// 暂停位: 购买商店物品
+pub const PAUSE_BUY: u32 = 1 << 12;
+/// 暂停位: 领取金币奖励
+pub const PAUSE_CLAIM_REWARDS: u32 = 1 << 13;
+/// 暂停位: 领取掉落物品
+pub const PAUSE_CLAIM_LOOT: u32 = 1 << 14;
+/// 暂停位: 装备大炮
+pub const PAUSE_EQUIP_CANNONS: u32 = 1 << 15;
+/// 暂停位: 补充朗姆酒
+pub const PAUSE_STOCK_RUM: u32 = 1 << 16;
+/// 暂停位: 打捞船只
+pub const PAUSE_SALVAGE_SHIP: u32 = 1 << 17;
+/// 暂停位: 退还悬赏
+pub const PAUSE_REFUND_BOUNTY: u32 = 1 << 18;
+/// 所有暂停位
+pub const PAUSE_ALL: u32 = PAUSE_SPAWN_PLAYER
+    | PAUSE_MOVE_PLAYER
+    | PAUSE_SHOOT
+    | PAUSE_CTHULHU
+    | PAUSE_UPGRADE_SHIP
+    | PAUSE_REPAIR
+    | PAUSE_SPAWN_CHESTS
+    | PAUSE_NPC_TICK
+    | PAUSE_PLACE_BOUNTY
+    | PAUSE_JOIN_TOURNAMENT
+    | PAUSE_STORM_TICK
+    | PAUSE_CLAIM_ROYALE
+    | PAUSE_BUY
+    | PAUSE_CLAIM_REWARDS
+    | PAUSE_CLAIM_LOOT
+    | PAUSE_EQUIP_CANNONS
+    | PAUSE_STOCK_RUM
+    | PAUSE_SALVAGE_SHIP
+    | PAUSE_REFUND_BOUNTY;
+
+/// 游戏配置账户,种子为"config"
+#[account]
+#[derive(InitSpace)]
+pub struct GameConfig {
+    /// 布局版本
+    pub version: u8,
+    /// 管理员
+    pub authority: Pubkey,
+    /// 暂停位图,每一位对应一条指令
+    pub paused: u32,
+}
+
+impl GameConfig {
+    /// 检查指令是否被暂停
+    ///
+    /// # 参数
+    /// * `flag` - 指令对应的暂停位
+    ///
+    /// # 返回值
+    /// * `Result<()>` - 被暂停时返回 GamePaused
+    pub fn ensure_not_paused(&self, flag: u32) -> Result<()> {
+        if self.paused & flag != 0 {
+            return Err(SevenSeasError::GamePaused.into());
+        }
+        Ok(())
+    }
+}
@@ -2,32 +2,96 @@
 pub use crate::errors::SevenSeasError;
 // 导入游戏相关常量
 use crate::PLAYER_KILL_REWARD;
-use crate::{Ship, CHEST_REWARD};
+use crate::{
+    generate_board, BoardGenParams, GeneratedTile, LegacyGameActionHistory, LegacyTile, LootTable,
+    PlayerProfile, Ship, AMMO_CHAIN_SHOT, AMMO_EXPLOSIVE_SHELL, AMMO_GRAPESHOT,
+    CHAIN_SHOT_PIN_SLOTS, CHAIN_SHOT_SPEED_PENALTY, CHEST_REWARD, CHEST_TIER_COMMON,
+    GRAPESHOT_CLOSE_MULTIPLIER, LEGACY_BOARD_SIZE, LEGACY_GAME_DATA_SPACE, LOOT_ITEM_CANNON,
+    SHIP_CLASSES, SHIP_SINK_LOSS_PERCENT, SHIP_VERSION,
+};
 // 导入 Anchor 相关模块
 use anchor_lang::prelude::*;
-use anchor_spl::token::Transfer;
-
-// 游戏棋盘大小常量
-const BOARD_SIZE_X: usize = 10;
-const BOARD_SIZE_Y: usize = 10;
+use std::cell::RefMut;
+use std::mem::size_of;
+
+// 游戏棋盘默认大小常量
+pub const DEFAULT_BOARD_WIDTH: u16 = 10;
+pub const DEFAULT_BOARD_HEIGHT: u16 = 10;
+// 棋盘最大边长,扩容后的棋盘不能超过这个大小
+pub const MAX_BOARD_SIZE: u16 = 64;
+// 玩家表大小,即棋盘上同时存在的最大船只数
+pub const MAX_PLAYERS: usize = 64;
+
+// 账户布局版本,最初的账户没有版本号,迁移后为版本1
+pub const GAME_DATA_VERSION: u8 = 1;
+pub const GAME_ACTIONS_VERSION: u8 = 1;
+pub const CHEST_VAULT_VERSION: u8 = 1;
+pub const TREASURY_VERSION: u8 = 1;
 
 // 棋盘格子状态常量
 const STATE_EMPTY: u8 = 0; // 空格子
 const STATE_PLAYER: u8 = 1; // 玩家所在格子
 const STATE_CHEST: u8 = 2; // 宝箱所在格子
+const STATE_ISLAND: u8 = 3; // 岛屿: 不可通行,阻挡炮弹
+const STATE_REEF: u8 = 4; // 暗礁: 不可通行,炮弹可以越过
+const STATE_PORT: u8 = 5; // 港口: 不可通行,阻挡炮弹,停靠在旁边的船只可以随时修理
+const STATE_STORM: u8 = 6; // 风暴: 大逃杀中的安全区外,不可驶入,炮弹可以越过
 
 // 游戏动作类型常量
 const GAME_ACTION_SHIP_SHOT: u8 = 0; // 船只射击
 const GAME_ACTION_SHIP_TAKEN_DAMAGE: u8 = 1; // 船只受伤
 const GAME_ACTION_SHIP_CTHULUH_ATTACKED_SHIP: u8 = 2; // 克苏鲁攻击船只
 const GAME_ACTION_SHIP_COINS_COLLECTED: u8 = 3; // 收集金币
+const GAME_ACTION_SHIP_REPAIRED: u8 = 4; // 船只修理
+const GAME_ACTION_SHIP_BOARDED: u8 = 5; // 接舷战,player为胜者,target为败者,damage为夺取的奖励
+const GAME_ACTION_SHIP_STORM_DAMAGE: u8 = 6; // 船只在风暴中受伤
 
 // 游戏奖励常量
 const CHEST_COIN_REWARD: u64 = 10; // 宝箱奖励金币数
 const DESTROY_SHIP_COIN_REWARD: u64 = 10; // 摧毁船只奖励金币数
 
-// 代币精度乘数
-pub const TOKEN_DECIMAL_MULTIPLIER: u64 = 1000000000;
+/// 不在港口时,船只至少这么多slot没有受到伤害才能修理
+pub const REPAIR_SAFE_SLOTS: u64 = 150;
+
+/// 接舷战中每门大炮(船员)提供的战力
+pub const BOARDING_CREW_WEIGHT: u64 = 5;
+/// 接舷战中每个船只等级提供的战力
+pub const BOARDING_LEVEL_WEIGHT: u64 = 20;
+/// 接舷战中随机战力的范围
+pub const BOARDING_RANDOMNESS: u64 = 50;
+/// 接舷战胜者夺取败者托管奖励的比例(百分比)
+pub const BOARDING_CAPTURE_PERCENT: u64 = 50;
+/// 接舷战中败者受到的伤害
+pub const BOARDING_DAMAGE: u64 = 10;
+/// 接舷战中发起方无论胜负都会受到的伤害,反复撞击刷随机数需要付出代价
+pub const BOARDING_RECOIL_DAMAGE: u64 = 5;
+
+/// NPC行为: 不是NPC,即玩家的船只
+pub const NPC_NONE: u8 = 0;
+/// NPC行为: 沿当前方向巡逻,遇到障碍时右转
+pub const NPC_PATROL: u8 = 1;
+/// NPC行为: 追击最近的玩家
+pub const NPC_HUNT: u8 = 2;
+/// NPC行为: 守护宝箱,只追击靠近宝箱的玩家
+pub const NPC_GUARD: u8 = 3;
+/// 守护型NPC追击玩家的范围(与守护点的曼哈顿距离)
+pub const NPC_GUARD_RADIUS: usize = 3;
+/// 击沉NPC的赏金,生成NPC时从宝箱国库转入宝箱金库
+pub const NPC_BOUNTY: u64 = PLAYER_KILL_REWARD;
+
+/// 团队模式中记分的队伍数量
+pub const MAX_TEAMS: usize = 8;
+/// 团队模式中分享击杀奖励的队友与击杀者的最远曼哈顿距离
+pub const TEAM_SHARE_RADIUS: usize = 3;
+
+/// 大逃杀阶段: 未开启
+pub const ROYALE_OFF: u8 = 0;
+/// 大逃杀阶段: 报名中,玩家可以出海
+pub const ROYALE_OPEN: u8 = 1;
+/// 大逃杀阶段: 比赛中,不能再出海,风暴逐圈收缩
+pub const ROYALE_RUNNING: u8 = 2;
+/// 风暴每次对其中的船只造成的伤害
+pub const ROYALE_STORM_DAMAGE: u64 = 10;
 
 // 重置游戏账户结构
 #[derive(Accounts)]
@@ -56,6 +120,21 @@ pub struct ResetShip<'info> {
         bump,
     )]
     pub game_data_account: AccountLoader<'info, GameDataAccount>, // 游戏数据账户
+
+    // 船只账户,返航后清除出海标记,已出海时必须是船只所在的棋盘
+    #[account(
+        mut,
+        seeds = [b"ship", nft_account.key().as_ref()],
+        bump,
+        constraint = ship.version == SHIP_VERSION @ SevenSeasError::AccountNeedsMigration,
+        constraint = !ship.deployed || ship.board == game_data_account.key() @ SevenSeasError::WrongBoard
+    )]
+    pub ship: Account<'info, Ship>,
+
+    /// NFT账户,目前就是玩家EOA账户
+    /// CHECK: 只能重置自己的船只
+    #[account(constraint = nft_account.key() == signer.key() @ SevenSeasError::Unauthorized)]
+    pub nft_account: AccountInfo<'info>,
 }
 
 /*
@@ -65,24 +144,59 @@ GameDataAccount 结构体定义
 - unsafe: 不进行内存安全检查
 - packed: 数据按字节对齐
 - repr(packed): 以最紧凑的方式布局结构体内存,不添加填充字节
-- Default: 允许使用默认值初始化结构体
 
 这些属性的组合在 Solana 程序中很常见,因为:
 1. 需要高效的内存访问(zero_copy)
 2. 需要精确的内存布局(packed)
-3. 需要能够创建默认实例(Default)
 
-对于存储游戏状态的 BoardAccount 特别重要,因为它需要:
-1. 高效访问游戏数据
-2. 在链上存储时节省空间
-3. 能够方便地初始化新的游戏状态
+GameDataAccount 只是账户的头部,棋盘格子紧跟在头部之后:
+| 8字节鉴别器 | GameDataAccount | width * height 个 Tile |
+格子数量取决于棋盘大小,所以不放在结构体里,而是通过 GameBoard 按头部中的宽高切分账户数据。
+这样棋盘可以通过 realloc 扩容,而不受单个结构体大小的限制。
 */
 #[account(zero_copy(unsafe))]
 #[repr(packed)]
-#[derive(Default)]
 pub struct GameDataAccount {
-    board: [[Tile; BOARD_SIZE_X]; BOARD_SIZE_Y], // 游戏棋盘数组
-    action_id: u64,                              // 动作ID计数器
+    version: u8,                        // 布局版本
+    authority: Pubkey,                  // 管理员,可以扩容棋盘
+    width: u16,                         // 棋盘宽度
+    height: u16,                        // 棋盘高度
+    action_id: u64,                     // 动作ID计数器
+    players: [PlayerSlot; MAX_PLAYERS], // 玩家表,格子通过索引引用
+    open_ocean: u8,                     // 是否为开放海域中的区块(1为区块)
+    chunk_x: u16,                       // 区块在世界中的x坐标
+    chunk_y: u16,                       // 区块在世界中的y坐标
+    wreck_percent: u8,                  // 残骸比例(百分比),0表示不留残骸
+    chest_density: u16,                 // 目标宝箱密度,每1000个水域格子中的宝箱数
+    max_chests: u16,                    // 最多宝箱数,0表示由生成玩家时附带生成宝箱
+    team_mode: u8,                      // 是否开启团队模式(1为开启)
+    scores: [TeamScore; MAX_TEAMS],     // 本局各队伍的得分
+    royale: u8,                         // 大逃杀阶段,见 ROYALE_* 常量
+    storm: u16,                         // 风暴圈数,离边缘的距离小于它的格子在风暴中
+    storm_interval_slots: u64,          // 风暴两次收缩之间的最少slot数
+    last_storm_slot: u64,               // 上次风暴收缩的slot
+    royale_pot: u64,                    // 大逃杀奖池(lamports),托管在宝箱金库中
+    epoch: u64,                         // 重置次数,用于区分被重置移除和被击沉的船只
+}
+
+// 一个队伍在本局中的得分
+#[zero_copy(unsafe)]
+#[repr(packed)]
+#[derive(Default)]
+pub struct TeamScore {
+    team: u16,  // 队伍编号,即公会编号,为0时表示空位 (2字节)
+    score: u64, // 击杀和收集宝箱的次数 (8字节)
+}
+
+// 玩家表中的一项
+#[zero_copy(unsafe)]
+#[repr(packed)]
+#[derive(Default)]
+pub struct PlayerSlot {
+    player: Pubkey, // 玩家公钥,为默认值时表示空位 (32字节)
+    avatar: Pubkey, // 头像公钥,用于客户端显示 (32字节)
+    x: u16,         // 船只所在格子的x坐标 (2字节)
+    y: u16,         // 船只所在格子的y坐标 (2字节)
 }
 
 // 棋盘格子结构体
@@ -91,23 +205,44 @@ pub struct GameDataAccount {
 #[derive(Default)]
 //#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct Tile {
-    player: Pubkey,      // 玩家公钥 (32字节)
-    state: u8,           // 格子状态 (1字节)
-    health: u64,         // 生命值 (8字节)
-    damage: u64,         // 伤害值 (8字节)
-    range: u16,          // 攻击范围 (2字节)
-    collect_reward: u64, // 收集奖励 (8字节)
-    avatar: Pubkey,      // 头像公钥,用于客户端显示 (32字节)
-    look_direction: u8,  // 朝向(上、右、下、左) (1字节)
-    ship_level: u16,     // 船只等级 (2字节)
-    start_health: u64,   // 初始生命值,用于客户端显示血条 (8字节)
+    state: u8,             // 格子状态 (1字节)
+    player: u16,           // 玩家表索引 (2字节)
+    health: u32,           // 生命值 (4字节)
+    damage: u32,           // 伤害值 (4字节)
+    range: u8,             // 攻击范围 (1字节)
+    collect_reward: u64,   // 收集奖励 (8字节)
+    look_direction: u8,    // 朝向(上、右、下、左) (1字节)
+    ship_level: u8,        // 船只等级 (1字节)
+    start_health: u32,     // 初始生命值,用于客户端显示血条 (4字节)
+    speed: u8,             // 移动速度,由船型决定 (1字节)
+    last_shot_slot: u64,   // 上次射击的slot,0表示从未射击 (8字节)
+    last_move_slot: u64,   // 上次移动的slot,0表示从未移动,被链弹命中后推到将来 (8字节)
+    last_damage_slot: u64, // 上次受到伤害的slot,0表示从未受伤 (8字节)
+    chest_tier: u8,        // 宝箱等级,见 CHEST_TIER_* 常量 (1字节)
+    npc: u8,               // NPC行为,见 NPC_* 常量,玩家的船只为0 (1字节)
+    home_x: u16,           // NPC守护点的x坐标 (2字节)
+    home_y: u16,           // NPC守护点的y坐标 (2字节)
+    team: u16,             // 队伍编号,即公会编号,0表示不属于任何队伍 (2字节)
 }
 
+// 格子存放在账户剩余数据中,需要按字节切片直接转换
+// Tile 只包含整数字段且没有填充字节,任意字节组合都是合法值
+unsafe impl bytemuck::Zeroable for Tile {}
+unsafe impl bytemuck::Pod for Tile {}
+
+// 任何大小的棋盘在当前布局下都不会恰好占用最初账户的大小,据此识别最初的账户
+const LEGACY_TILE_BYTES: usize = LEGACY_GAME_DATA_SPACE - 8 - size_of::<GameDataAccount>();
+const _: () =
+    assert!(LEGACY_TILE_BYTES / size_of::<Tile>() * size_of::<Tile>() != LEGACY_TILE_BYTES);
+
 // 游戏动作历史记录账户
 #[account]
 #[derive(InitSpace)]
 pub struct GameActionHistory {
-    id_counter: u64, // ID计数器
+    version: u8,               // 布局版本
+    id_counter: u64,           // ID计数器
+    shoot_cooldown_slots: u64, // 两次射击之间至少间隔的slot数,客户端据此显示装填时间
+    move_cooldown_slots: u64,  // 速度为1的船只两次移动之间至少间隔的slot数
 
     #[max_len(100)]
     game_actions: Vec<GameAction>, // 游戏动作记录数组
@@ -123,43 +258,647 @@ pub struct GameAction {
     damage: u64,     // 伤害值 (8字节)
 }
 
+// 一发炮弹,攻击格子时按弹药类型结算效果
+#[derive(Clone, Copy)]
+struct Shot {
+    damage: u64, // 伤害值
+    ammo: u8,    // 弹药类型
+    team: u16,   // 射击者的队伍编号,团队模式中不会伤到队友
+}
+
+// 一次击沉,射击和接舷战击沉船只时为击杀者记录
+#[derive(Clone, Copy)]
+struct Kill {
+    team: u16,      // 击杀者的队伍编号
+    target: Pubkey, // 被击沉的船主
+    ship_level: u8, // 被击沉船只的等级,决定奖励金币
+    reward: u64,    // 击杀者获得的SOL奖励
+}
+
+/// 完整移动指令传入的账户,收集宝箱和接舷战时用于发放奖励和记录动作
+pub struct MoveContext<'c, 'info> {
+    /// 移动的玩家
+    pub player: AccountInfo<'info>,
+    /// 宝箱金库,发放宝箱奖励
+    pub chest_vault: AccountInfo<'info>,
+    /// 玩家档案,记入获得的金币和统计
+    pub profile: &'c mut PlayerProfile,
+    /// 被撞击船只船主的档案,与玩家的船只发生接舷战时需要传入
+    pub target_profile: Option<&'c mut PlayerProfile>,
+    /// 游戏动作历史,提供冷却时间并记录动作
+    pub game_actions: &'c mut GameActionHistory,
+    /// 宝箱掉落表,没有时收集宝箱只获得固定的金币奖励
+    pub loot_table: Option<&'c LootTable>,
+}
+
+// 冷却时间是否已结束,从未执行过的动作没有冷却
+fn cooldown_ready(last_slot: u64, cooldown: u64, now: u64) -> bool {
+    last_slot == 0 || now >= last_slot.saturating_add(cooldown)
+}
+
+// 把u64数值压缩到u32,超出范围时取最大值
+fn saturate_u32(value: u64) -> u32 {
+    u32::try_from(value).unwrap_or(u32::MAX)
+}
+
 impl GameDataAccount {
+    /// 计算指定大小的棋盘所需的账户空间
+    pub const fn space(width: u16, height: u16) -> usize {
+        8 + size_of::<GameDataAccount>() + (width as usize) * (height as usize) * size_of::<Tile>()
+    }
+
+    /// 初始化头部
+    pub fn init(&mut self, authority: Pubkey, width: u16, height: u16) {
+        self.version = GAME_DATA_VERSION;
+        self.authority = authority;
+        self.width = width;
+        self.height = height;
+        self.action_id = 0;
+    }
+
+    /// 布局版本
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// 管理员
+    pub fn authority(&self) -> Pubkey {
+        self.authority
+    }
+
+    /// 棋盘宽度和高度
+    pub fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    /// 标记为开放海域中的区块
+    pub fn set_chunk(&mut self, chunk_x: u16, chunk_y: u16) {
+        self.open_ocean = 1;
+        self.chunk_x = chunk_x;
+        self.chunk_y = chunk_y;
+    }
+
+    /// 设置被击沉的船只留在残骸中的托管奖励比例(百分比)
+    pub fn set_wreck_percent(&mut self, percent: u8) -> Result<()> {
+        if percent > 100 {
+            return Err(SevenSeasError::InvalidWreckPercent.into());
+        }
+        self.wreck_percent = percent;
+        Ok(())
+    }
+
+    /// 被击沉的船只留在残骸中的托管奖励比例(百分比)
+    pub fn wreck_percent(&self) -> u8 {
+        self.wreck_percent
+    }
+
+    /// 设置由 spawn_chests 维持的宝箱密度
+    ///
+    /// `max_chests` 为0时关闭,宝箱重新由生成玩家时附带生成
+    pub fn set_chest_spawning(&mut self, chest_density: u16, max_chests: u16) {
+        self.chest_density = chest_density;
+        self.max_chests = max_chests;
+    }
+
+    /// 开启或关闭团队模式,开启时清空上一局的队伍得分
+    pub fn set_team_mode(&mut self, enabled: bool) {
+        self.team_mode = enabled as u8;
+        if enabled {
+            self.scores = [TeamScore::default(); MAX_TEAMS];
+        }
+    }
+
+    /// 是否开启团队模式
+    pub fn team_mode(&self) -> bool {
+        self.team_mode == 1
+    }
+
+    /// 本局各队伍的得分,按(队伍编号, 得分)返回
+    pub fn team_scores(&self) -> Vec<(u16, u64)> {
+        let scores = self.scores;
+        scores
+            .iter()
+            .filter(|entry| entry.team != 0)
+            .map(|entry| (entry.team, entry.score))
+            .collect()
+    }
+
+    /// 大逃杀阶段,见 ROYALE_* 常量
+    pub fn royale(&self) -> u8 {
+        self.royale
+    }
+
+    /// 大逃杀奖池(lamports)
+    pub fn royale_pot(&self) -> u64 {
+        self.royale_pot
+    }
+
+    /// 重置次数
+    ///
+    /// 船只出海时记录这个值,之后不一致说明船只是被重置整个棋盘移除的,而不是被击沉
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// 区块坐标,不是区块时返回None
+    pub fn chunk(&self) -> Option<(u16, u16)> {
+        if self.open_ocean == 1 {
+            Some((self.chunk_x, self.chunk_y))
+        } else {
+            None
+        }
+    }
+
+    /// 是否为最初的账户布局: 没有头部,只有 10x10 的格子和动作ID
+    pub fn is_legacy(data: &[u8]) -> bool {
+        data.len() == LEGACY_GAME_DATA_SPACE
+    }
+
+    /// 原地把最初的账户数据迁移到当前布局
+    ///
+    /// 最初的账户没有头部,格子中直接存放玩家和头像公钥,数值字段也更宽,所以逐个格子转换。
+    /// 调用前账户数据必须已经 realloc 到 10x10 棋盘在当前布局下的大小
+    ///
+    /// # 参数
+    /// * `data` - 账户数据
+    /// * `authority` - 迁移后的管理员,最初的账户没有管理员
+    ///
+    /// # 返回值
+    /// * `Result<()>` - 船只超过玩家表大小时返回 PlayerTableFull
+    pub fn migrate_legacy(data: &mut [u8], authority: Pubkey) -> Result<()> {
+        let tile_count = LEGACY_BOARD_SIZE as usize * LEGACY_BOARD_SIZE as usize;
+
+        // 新布局会覆盖旧的格子,先把它们读出来
+        let mut reader = &data[8..];
+        let mut legacy = Vec::with_capacity(tile_count);
+        for _ in 0..tile_count {
+            legacy.push(LegacyTile::deserialize(&mut reader)?);
+        }
+        let action_id = u64::deserialize(&mut reader)?;
+
+        let data = &mut data[8..];
+        data.fill(0);
+        let (header, tiles) = data.split_at_mut(size_of::<GameDataAccount>());
+        let header: &mut GameDataAccount = bytemuck::from_bytes_mut(header);
+        let tiles: &mut [Tile] =
+            bytemuck::cast_slice_mut(&mut tiles[..tile_count * size_of::<Tile>()]);
+        header.version = GAME_DATA_VERSION;
+        header.authority = authority;
+        header.width = LEGACY_BOARD_SIZE;
+        header.height = LEGACY_BOARD_SIZE;
+        header.action_id = action_id;
+
+        // 每艘船占用玩家表中的一项,坐标之后统一重建
+        let mut players = 0;
+        for (old, tile) in legacy.iter().zip(tiles.iter_mut()) {
+            tile.state = old.state;
+            tile.health = saturate_u32(old.health);
+            tile.damage = saturate_u32(old.damage);
+            tile.range = u8::try_from(old.range).unwrap_or(u8::MAX);
+            tile.collect_reward = old.collect_reward;
+            tile.look_direction = old.look_direction;
+            tile.ship_level = u8::try_from(old.ship_level).unwrap_or(u8::MAX);
+            tile.start_health = saturate_u32(old.start_health);
+            if old.state == STATE_PLAYER {
+                let slot = header
+                    .players
+                    .get_mut(players)
+                    .ok_or(SevenSeasError::PlayerTableFull)?;
+                slot.player = old.player;
+                slot.avatar = old.avatar;
+                tile.player = players as u16;
+                players += 1;
+            }
+        }
+        header.index_players(tiles);
+        Ok(())
+    }
+
+    /// 根据格子重建玩家表中的船只坐标
+    fn index_players(&mut self, tiles: &[Tile]) {
+        let height = self.height as usize;
+        for (index, tile) in tiles.iter().enumerate() {
+            if tile.state == STATE_PLAYER {
+                let slot = &mut self.players[tile.player as usize];
+                slot.x = (index / height) as u16;
+                slot.y = (index % height) as u16;
+            }
+        }
+    }
+
+    /// 玩家的船只是否在棋盘上
+    pub fn is_on_board(&self, player: &Pubkey) -> bool {
+        let players = self.players;
+        players.iter().any(|slot| slot.player == *player)
+    }
+}
+
+/// 游戏棋盘
+///
+/// 同时借用账户中的头部和格子数组,所有的游戏逻辑都在这里实现
+pub struct GameBoard<'a> {
+    header: RefMut<'a, GameDataAccount>,
+    tiles: RefMut<'a, [Tile]>,
+    sunk: Vec<Pubkey>, // 本次指令中被玩家击沉的船主,用于发放悬赏
+}
+
+impl<'a> GameBoard<'a> {
+    /// 玩家的船只是否在棋盘上
+    pub fn is_on_board(&self, player: &Pubkey) -> bool {
+        self.header.is_on_board(player)
+    }
+
+    /// 重置次数
+    pub fn epoch(&self) -> u64 {
+        self.header.epoch()
+    }
+
+    /// 加载已初始化的游戏数据账户
+    pub fn load_mut<'info>(loader: &'a AccountLoader<'info, GameDataAccount>) -> Result<Self> {
+        // 先用 load_mut 检查账户所有者、鉴别器和可写性
+        drop(loader.load_mut()?);
+        Self::from_account(loader.as_ref())
+    }
+
+    /// 加载正在初始化的游戏数据账户(鉴别器在指令结束时才写入)
+    pub fn load_init<'info>(loader: &'a AccountLoader<'info, GameDataAccount>) -> Result<Self> {
+        drop(loader.load_init()?);
+        Self::from_account(loader.as_ref())
+    }
+
+    // 按头部中的宽高把账户数据切分为头部和格子数组
+    fn from_account<'info>(account: &'a AccountInfo<'info>) -> Result<Self> {
+        let data = account.try_borrow_mut_data()?;
+        let (header, tiles) =
+            RefMut::map_split(data, |data| data[8..].split_at_mut(size_of::<GameDataAccount>()));
+        let header: RefMut<GameDataAccount> = RefMut::map(header, |bytes| {
+            bytemuck::from_bytes_mut(bytes)
+        });
+        if header.version != GAME_DATA_VERSION {
+            return Err(SevenSeasError::AccountNeedsMigration.into());
+        }
+
+        let tile_count = header.width as usize * header.height as usize;
+        if tiles.len() < tile_count * size_of::<Tile>() {
+            return Err(SevenSeasError::InvalidBoardSize.into());
+        }
+        let tiles = RefMut::map(tiles, |bytes| {
+            bytemuck::cast_slice_mut(&mut bytes[..tile_count * size_of::<Tile>()])
+        });
+
+        Ok(Self {
+            header,
+            tiles,
+            sunk: Vec::new(),
+        })
+    }
+
+    /// 扩大棋盘,保留现有格子的位置
+    ///
+    /// 调用前账户数据必须已经 realloc 到 `GameDataAccount::space(width, height)`
+    pub fn resize<'info>(
+        loader: &AccountLoader<'info, GameDataAccount>,
+        width: u16,
+        height: u16,
+    ) -> Result<()> {
+        drop(loader.load_mut()?);
+        let account: &AccountInfo = loader.as_ref();
+        let mut data = account.try_borrow_mut_data()?;
+        if data.len() < GameDataAccount::space(width, height) {
+            return Err(SevenSeasError::InvalidBoardSize.into());
+        }
+
+        let (header_bytes, tile_bytes) = data[8..].split_at_mut(size_of::<GameDataAccount>());
+        let header: &mut GameDataAccount = bytemuck::from_bytes_mut(header_bytes);
+        if header.version != GAME_DATA_VERSION {
+            return Err(SevenSeasError::AccountNeedsMigration.into());
+        }
+        let (old_width, old_height) = (header.width as usize, header.height as usize);
+        let (new_width, new_height) = (width as usize, height as usize);
+        let tile_size = size_of::<Tile>();
+
+        // 按 x * height + y 排列,扩容后每个格子的新索引都不小于旧索引,
+        // 所以从后往前移动不会覆盖尚未移动的格子
+        for x in (0..old_width).rev() {
+            for y in (0..old_height).rev() {
+                let from = (x * old_height + y) * tile_size;
+                let to = (x * new_height + y) * tile_size;
+                tile_bytes.copy_within(from..from + tile_size, to);
+            }
+        }
+        // 新增的格子清空为空海域
+        for x in 0..new_width {
+            for y in 0..new_height {
+                if x >= old_width || y >= old_height {
+                    let at = (x * new_height + y) * tile_size;
+                    tile_bytes[at..at + tile_size].fill(0);
+                }
+            }
+        }
+
+        header.width = width;
+        header.height = height;
+        msg!("Board resized to {}x{}", width, height);
+        Ok(())
+    }
+
+    /// 初始化头部
+    pub fn init(&mut self, authority: Pubkey) {
+        let (width, height) = (self.header.width, self.header.height);
+        self.header.init(authority, width, height);
+    }
+
+    // 棋盘宽度
+    fn width(&self) -> usize {
+        self.header.width as usize
+    }
+
+    // 棋盘高度
+    fn height(&self) -> usize {
+        self.header.height as usize
+    }
+
+    // 读取指定格子
+    fn tile(&self, position: (usize, usize)) -> Tile {
+        self.tiles[position.0 * self.height() + position.1]
+    }
+
+    // 获取指定格子的可变引用
+    fn tile_mut(&mut self, position: (usize, usize)) -> &mut Tile {
+        let index = position.0 * self.height() + position.1;
+        &mut self.tiles[index]
+    }
+
+    // 第n个空格子的坐标,按格子索引排序
+    // 随机选择空格子时先计数再取第n个,大棋盘上也不需要分配内存
+    fn nth_empty_tile(&self, n: usize) -> Option<(usize, usize)> {
+        let height = self.height();
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| tile.state == STATE_EMPTY)
+            .nth(n)
+            .map(|(index, _)| (index / height, index % height))
+    }
+
+    // 空格子数量
+    fn count_empty_tiles(&self) -> usize {
+        self.tiles
+            .iter()
+            .filter(|tile| tile.state == STATE_EMPTY)
+            .count()
+    }
+
+    // 宝箱旁边的空格子,连同宝箱的坐标
+    fn guard_tiles(&self) -> impl Iterator<Item = ((usize, usize), (usize, usize))> + '_ {
+        let height = self.height();
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| tile.state == STATE_CHEST)
+            .flat_map(move |(index, _)| {
+                let chest = (index / height, index % height);
+                (0..4).filter_map(move |direction| {
+                    let next = self.calculate_new_position(chest, direction).ok()?;
+                    (self.tile(next).state == STATE_EMPTY).then_some((next, chest))
+                })
+            })
+    }
+
+    // 查询格子所属玩家的公钥
+    fn player_key(&self, tile: &Tile) -> Pubkey {
+        self.header.players[tile.player as usize].player
+    }
+
+    // 查找玩家在玩家表中的索引
+    fn find_player_slot(&self, player_key: &Pubkey) -> Option<u16> {
+        (0..MAX_PLAYERS)
+            .find(|i| self.header.players[*i].player == *player_key)
+            .map(|i| i as u16)
+    }
+
+    // 在玩家表中为玩家分配一个空位,并记录船只所在格子
+    fn allocate_player_slot(
+        &mut self,
+        player: Pubkey,
+        avatar: Pubkey,
+        position: (usize, usize),
+    ) -> Result<u16> {
+        match self.find_player_slot(&Pubkey::default()) {
+            None => Err(SevenSeasError::PlayerTableFull.into()),
+            Some(index) => {
+                self.header.players[index as usize] = PlayerSlot {
+                    player,
+                    avatar,
+                    x: position.0 as u16,
+                    y: position.1 as u16,
+                };
+                Ok(index)
+            }
+        }
+    }
+
+    // 把船只从当前格子移动到新格子,并更新玩家表中的坐标
+    fn move_ship(&mut self, current_pos: (usize, usize), new_pos: (usize, usize), direction: u8) {
+        let mut tile = self.tile(current_pos);
+        tile.look_direction = direction;
+        *self.tile_mut(new_pos) = tile;
+        self.tile_mut(current_pos).state = STATE_EMPTY;
+
+        let slot = &mut self.header.players[tile.player as usize];
+        slot.x = new_pos.0 as u16;
+        slot.y = new_pos.1 as u16;
+    }
+
+    // 释放玩家表中的位置
+    fn release_player_slot(&mut self, index: u16) {
+        self.header.players[index as usize] = PlayerSlot::default();
+    }
+
+    // 移除指定格子上的船只
+    fn remove_ship(&mut self, position: (usize, usize)) {
+        let tile = self.tile_mut(position);
+        tile.state = STATE_EMPTY;
+        let index = tile.player;
+        self.release_player_slot(index);
+    }
+
+    // 检查射击冷却时间,并记录本次射击的slot
+    fn start_shot_cooldown(&mut self, position: (usize, usize), cooldown: u64) -> Result<()> {
+        let now = Clock::get()?.slot;
+        let tile = self.tile_mut(position);
+        if !cooldown_ready(tile.last_shot_slot, cooldown, now) {
+            return Err(SevenSeasError::ActionOnCooldown.into());
+        }
+        tile.last_shot_slot = now;
+        Ok(())
+    }
+
+    // 检查移动冷却时间,并记录本次移动的slot
+    //
+    // 冷却时间按船只速度缩短,格子随船只移动,记录也会跟着船只走
+    fn start_move_cooldown(&mut self, position: (usize, usize), cooldown: u64) -> Result<()> {
+        let now = Clock::get()?.slot;
+        let tile = self.tile_mut(position);
+        let cooldown = cooldown / tile.speed.max(1) as u64;
+        if !cooldown_ready(tile.last_move_slot, cooldown, now) {
+            return Err(SevenSeasError::ActionOnCooldown.into());
+        }
+        tile.last_move_slot = now;
+        Ok(())
+    }
+
+    // 相邻区块相对于本区块的方向,两个棋盘不是相邻区块时返回None
+    fn neighbour_offset(&self, other: &GameBoard) -> Option<(i64, i64)> {
+        let (x, y) = self.header.chunk()?;
+        let (other_x, other_y) = other.header.chunk()?;
+        let offset = (other_x as i64 - x as i64, other_y as i64 - y as i64);
+        match offset {
+            (0, -1) | (1, 0) | (0, 1) | (-1, 0) => Some(offset),
+            _ => None,
+        }
+    }
+
+    // 相邻区块的原点在本区块坐标系中的位置,两个棋盘不是相邻区块时返回None
+    fn neighbour_origin(&self, other: &GameBoard) -> Option<(i64, i64)> {
+        Some(match self.neighbour_offset(other)? {
+            (-1, 0) => (-(other.width() as i64), 0),
+            (1, 0) => (self.width() as i64, 0),
+            (0, -1) => (0, -(other.height() as i64)),
+            _ => (0, self.height() as i64),
+        })
+    }
+
+    // 把越过棋盘边缘的坐标转换为相邻区块中的坐标
+    fn position_in_neighbour(
+        &self,
+        neighbour: &GameBoard,
+        x: i64,
+        y: i64,
+    ) -> Option<(usize, usize)> {
+        let (width, height) = (self.width() as i64, self.height() as i64);
+        let offset = self.neighbour_offset(neighbour)?;
+        let (nx, ny) = match offset {
+            (-1, 0) if x < 0 && (0..height).contains(&y) => (neighbour.width() as i64 + x, y),
+            (1, 0) if x >= width && (0..height).contains(&y) => (x - width, y),
+            (0, -1) if y < 0 && (0..width).contains(&x) => (x, neighbour.height() as i64 + y),
+            (0, 1) if y >= height && (0..width).contains(&x) => (x, y - height),
+            _ => return None,
+        };
+        if nx < 0 || ny < 0 || nx >= neighbour.width() as i64 || ny >= neighbour.height() as i64 {
+            return None;
+        }
+        Some((nx as usize, ny as usize))
+    }
+
     // 打印游戏棋盘状态(仅用于本地调试)
     pub fn print(&mut self) {
         // print will only work locally for debugging otherwise it eats too much compute
         /*
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                let tile = self.board[x][y  ];
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                let tile = self.tile((x, y));
                 if tile.state == STATE_EMPTY {
                     msg!("empty")
                 } else {
-                    msg!("{} {}", tile.player, tile.state)
+                    msg!("{} {}", self.player_key(&tile), tile.state)
                 }
             }
         }*/
     }
 
-    // 重置整个游戏棋盘
+    // 重置整个游戏棋盘(保留岛屿和暗礁等地形)
+    //
+    // 重置次数加一,被移除的船只下次出海或打捞时不会被视为已被击沉
     pub fn reset(&mut self) -> Result<()> {
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                self.board[x][y].state = STATE_EMPTY
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                if !Self::is_terrain(self.tile((x, y)).state) {
+                    self.tile_mut((x, y)).state = STATE_EMPTY
+                }
             }
         }
+        for index in 0..MAX_PLAYERS {
+            self.release_player_slot(index as u16);
+        }
+        self.header.epoch += 1;
         Ok(())
     }
 
-    // 重置指定玩家的船只
-    pub fn reset_ship(&mut self, ship_owner: Pubkey) -> Result<()> {
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                if self.board[x][y].player == ship_owner && self.board[x][y].state == STATE_PLAYER {
-                    self.board[x][y].state = STATE_EMPTY
+    // 判断格子是否为地形(岛屿、暗礁或港口)
+    fn is_terrain(state: u8) -> bool {
+        state == STATE_ISLAND || state == STATE_REEF || state == STATE_PORT
+    }
+
+    // 判断格子是否阻挡炮弹(岛屿或港口)
+    fn blocks_shots(state: u8) -> bool {
+        state == STATE_ISLAND || state == STATE_PORT
+    }
+
+    /// 根据种子生成地形和初始宝箱
+    ///
+    /// # 参数
+    /// * `params` - 生成参数
+    ///
+    /// # 返回值
+    /// * `Result<u64>` - 成功返回生成的宝箱数量
+    pub fn generate(&mut self, params: &BoardGenParams) -> Result<u64> {
+        if !params.is_valid() {
+            return Err(SevenSeasError::InvalidBoardGenParams.into());
+        }
+
+        let mut chests: u64 = 0;
+        let (width, height) = (self.width(), self.height());
+        let tiles = generate_board(params, width, height);
+        for x in 0..width {
+            for y in 0..height {
+                match tiles[x * height + y] {
+                    GeneratedTile::Water => {
+                        *self.tile_mut((x, y)) = Tile::default();
+                    }
+                    GeneratedTile::Island => {
+                        *self.tile_mut((x, y)) = Tile {
+                            state: STATE_ISLAND,
+                            ..Tile::default()
+                        };
+                    }
+                    GeneratedTile::Reef => {
+                        *self.tile_mut((x, y)) = Tile {
+                            state: STATE_REEF,
+                            ..Tile::default()
+                        };
+                    }
+                    GeneratedTile::Port => {
+                        *self.tile_mut((x, y)) = Tile {
+                            state: STATE_PORT,
+                            ..Tile::default()
+                        };
+                    }
+                    GeneratedTile::Chest => {
+                        self.place_chest((x, y), CHEST_TIER_COMMON);
+                        chests += 1;
+                    }
                 }
             }
         }
-        Ok(())
+        msg!("Generated board with seed {} and {} chests", params.seed, chests);
+        Ok(chests)
+    }
+
+    // 重置指定玩家的船只,返回船只是否在棋盘上
+    pub fn reset_ship(&mut self, ship_owner: Pubkey) -> Result<bool> {
+        let Some(index) = self.find_player_slot(&ship_owner) else {
+            return Ok(false);
+        };
+        if let Ok(position) = self.find_player_position(&ship_owner) {
+            // 大逃杀中途返航的船只托管的奖励进入奖池
+            if self.header.royale == ROYALE_RUNNING {
+                self.header.royale_pot += self.tile(position).collect_reward;
+            }
+            self.tile_mut(position).state = STATE_EMPTY;
+        }
+        self.release_player_slot(index);
+        Ok(true)
     }
 
     // 计算两点间欧几里得距离
@@ -170,16 +909,11 @@ impl GameDataAccount {
     }
 
     // 克苏鲁攻击逻辑
-    pub fn cthulhu<'info>(
+    pub fn cthulhu(
         &mut self,
         _player: AccountInfo,
         game_actions: &mut GameActionHistory,
         _chest_vault: AccountInfo,
-        _vault_token_account: AccountInfo<'info>,
-        _player_token_account: AccountInfo<'info>,
-        _token_account_owner_pda: AccountInfo<'info>,
-        _token_program: AccountInfo<'info>,
-        _token_owner_bump: u8,
     ) -> Result<()> {
         let mut smallest_distance: f64 = 100000.0;
         let mut attacked_player_position: Option<(usize, usize)> = None;
@@ -187,17 +921,17 @@ impl GameDataAccount {
         let cthulhu_position: (usize, usize) = (0, 0);
 
         // 寻找最近的玩家
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                let tile = self.board[x][y];
-                if tile.state == STATE_PLAYER {
-                    let distance =
-                        Self::euclidean_distance(&x, &cthulhu_position.0, &y, &cthulhu_position.0);
-                    if distance < smallest_distance {
-                        smallest_distance = distance;
-                        attacked_player_position = Some((x, y));
-                    }
-                }
+        for index in 0..MAX_PLAYERS {
+            let slot = self.header.players[index];
+            if slot.player == Pubkey::default() {
+                continue;
+            }
+            let (x, y) = (slot.x as usize, slot.y as usize);
+            let distance =
+                Self::euclidean_distance(&x, &cthulhu_position.0, &y, &cthulhu_position.0);
+            if distance < smallest_distance {
+                smallest_distance = distance;
+                attacked_player_position = Some((x, y));
             }
         }
 
@@ -207,36 +941,41 @@ impl GameDataAccount {
                 return Err(SevenSeasError::CouldNotFindAShipToAttack.into());
             }
             Some(val) => {
-                let tile = &mut self.board[val.0][val.1];
+                let tile = self.tile(val);
+                let attacked_player = self.player_key(&tile);
 
-                let mut rng = XorShift64 { a: tile.health };
+                let mut rng = XorShift64 {
+                    a: tile.health as u64,
+                };
 
                 // 计算克苏鲁伤害值
                 let chtulu_damage: u64 = 10;
                 let damage_variant = ((chtulu_damage as f64) * 0.3).ceil() as u64;
                 let damage = chtulu_damage + ((rng.next() % damage_variant) + 1);
-                let option = tile.health.checked_sub(damage);
+                let option = (tile.health as u64).checked_sub(damage);
                 match option {
                     None => {
-                        tile.health = 0;
+                        self.tile_mut(val).health = 0;
                     }
-                    Some(val) => {
-                        tile.health = val;
+                    Some(val_health) => {
+                        self.tile_mut(val).health = val_health as u32;
                     }
                 }
 
-                // 如果生命值为0,移除玩家
-                if tile.health == 0 {
-                    tile.state = STATE_EMPTY;
+                self.tile_mut(val).last_damage_slot = Clock::get()?.slot;
+
+                // 如果生命值为0,移除玩家,托管奖励留在金库中
+                if self.tile(val).health == 0 {
+                    self.leave_wreck(val, tile, false);
                 }
 
                 // 记录攻击动作
                 let item = GameAction {
-                    action_id: self.action_id,
+                    action_id: self.header.action_id,
                     action_type: GAME_ACTION_SHIP_CTHULUH_ATTACKED_SHIP,
-                    player: tile.player.key(),
-                    target: tile.player.key(),
-                    damage: damage,
+                    player: attacked_player,
+                    target: attacked_player,
+                    damage,
                 };
                 self.add_new_game_action(game_actions, item);
 
@@ -253,31 +992,23 @@ impl GameDataAccount {
     }
 
     // 船只射击逻辑
-    pub fn shoot<'info>(
+    //
+    // 在开放海域中,射程越过棋盘边缘时,如果传入了相邻区块,炮弹会继续飞入相邻区块。
+    // ammo 为弹药类型,见 AMMO_* 常量,弹药代币的消耗在指令中处理
+    pub fn shoot(
         &mut self,
+        ammo: u8,
         player: AccountInfo,
         game_actions: &mut GameActionHistory,
         chest_vault: AccountInfo,
-        vault_token_account: AccountInfo<'info>,
-        player_token_account: AccountInfo<'info>,
-        token_account_owner_pda: AccountInfo<'info>,
-        token_program: AccountInfo<'info>,
-        token_owner_bump: u8,
-    ) -> Result<()> {
-        let mut player_position: Option<(usize, usize)> = None;
+        profile: &mut PlayerProfile,
+        mut neighbour: Option<&mut GameBoard>,
+    ) -> Result<u64> {
+        let (width, height) = (self.width(), self.height());
+        let mut reward = 0;
 
         // 寻找射击玩家的位置
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                let tile = self.board[x][y];
-                if tile.state == STATE_PLAYER {
-                    if tile.player == *player.key {
-                        player_position = Some((x, y));
-                    }
-                    msg!("{} {}", tile.player, tile.state);
-                }
-            }
-        }
+        let player_position = self.find_player_position(player.key).ok();
 
         // 如果找到玩家位置,执行射击
         match player_position {
@@ -286,90 +1017,131 @@ impl GameDataAccount {
             }
             Some(val) => {
                 msg!("Player position x:{} y:{}", val.0, val.1);
-                let player_tile: Tile = self.board[val.0][val.1];
+                self.start_shot_cooldown(val, game_actions.shoot_cooldown_slots)?;
+                let player_tile: Tile = self.tile(val);
                 let range_usize: usize = usize::from(player_tile.range);
-                let damage = player_tile.damage + 2;
-
-                // 根据射程范围进行射击
-                for range in 1..range_usize + 1 {
-                    // 向左射击
-                    if player_tile.look_direction % 2 == 0 && val.0 >= range {
-                        self.attack_tile(
-                            (val.0 - range, val.1),
-                            damage,
-                            player.clone(),
-                            chest_vault.clone(),
-                            game_actions,
-                            &vault_token_account,
-                            &player_token_account,
-                            &token_account_owner_pda,
-                            &token_program,
-                            token_owner_bump,
-                        )?;
-                    }
+                let damage = player_tile.damage as u64 + 2;
 
-                    // 向右射击
-                    if player_tile.look_direction % 2 == 0 && val.0 < BOARD_SIZE_X - range {
-                        self.attack_tile(
-                            (val.0 + range, val.1),
-                            damage,
-                            player.clone(),
-                            chest_vault.clone(),
-                            game_actions,
-                            &vault_token_account,
-                            &player_token_account,
-                            &token_account_owner_pda,
-                            &token_program,
-                            token_owner_bump,
-                        )?;
-                    }
+                // 朝向为偶数时向左右两侧开炮,为奇数时向下上两侧开炮
+                let directions: [(i64, i64); 2] = if matches!(player_tile.look_direction, 0 | 2) {
+                    [(-1, 0), (1, 0)]
+                } else {
+                    [(0, 1), (0, -1)]
+                };
+
+                // 根据射程范围进行射击,炮弹不能穿过岛屿
+                //
+                // 爆破弹不会逐格造成伤害,而是在第一艘船或射程尽头爆炸,
+                // impact 记录爆炸点以及爆炸点是否在相邻区块中
+                for (dx, dy) in directions {
+                    let mut impact: Option<(bool, (usize, usize))> = None;
+                    for range in 1..range_usize as i64 + 1 {
+                        let x = val.0 as i64 + dx * range;
+                        let y = val.1 as i64 + dy * range;
+                        let shot = Shot {
+                            damage: if ammo == AMMO_GRAPESHOT && range == 1 {
+                                damage * GRAPESHOT_CLOSE_MULTIPLIER
+                            } else {
+                                damage
+                            },
+                            ammo,
+                            team: player_tile.team,
+                        };
 
-                    // 向下射击
-                    if player_tile.look_direction % 2 == 1 && val.1 < BOARD_SIZE_Y - range {
-                        self.attack_tile(
-                            (val.0, val.1 + range),
-                            damage,
+                        if x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height {
+                            let target = (x as usize, y as usize);
+                            if Self::blocks_shots(self.tile(target).state) {
+                                break;
+                            }
+                            if ammo == AMMO_EXPLOSIVE_SHELL {
+                                impact = Some((false, target));
+                                if self.tile(target).state == STATE_PLAYER {
+                                    break;
+                                }
+                                continue;
+                            }
+                            reward += self.attack_tile(
+                                target,
+                                shot,
+                                player.clone(),
+                                chest_vault.clone(),
+                                game_actions,
+                                profile,
+                            )?;
+                            continue;
+                        }
+
+                        // 越过边缘,飞入相邻区块
+                        let Some(other) = neighbour.as_deref_mut() else {
+                            break;
+                        };
+                        let Some(target) = self.position_in_neighbour(other, x, y) else {
+                            break;
+                        };
+                        if Self::blocks_shots(other.tile(target).state) {
+                            break;
+                        }
+                        if ammo == AMMO_EXPLOSIVE_SHELL {
+                            impact = Some((true, target));
+                            if other.tile(target).state == STATE_PLAYER {
+                                break;
+                            }
+                            continue;
+                        }
+                        reward += other.attack_tile(
+                            target,
+                            shot,
                             player.clone(),
                             chest_vault.clone(),
                             game_actions,
-                            &vault_token_account,
-                            &player_token_account,
-                            &token_account_owner_pda,
-                            &token_program,
-                            token_owner_bump,
+                            profile,
                         )?;
                     }
 
-                    // 向上射击
-                    if player_tile.look_direction % 2 == 1 && val.1 >= range {
-                        self.attack_tile(
-                            (val.0, val.1 - range),
-                            damage,
-                            player.clone(),
-                            chest_vault.clone(),
-                            game_actions,
-                            &vault_token_account,
-                            &player_token_account,
-                            &token_account_owner_pda,
-                            &token_program,
-                            token_owner_bump,
-                        )?;
+                    // 爆破弹在爆炸点周围3×3范围内造成伤害
+                    let shot = Shot {
+                        damage,
+                        ammo,
+                        team: player_tile.team,
+                    };
+                    match (impact, neighbour.as_deref_mut()) {
+                        (Some((false, center)), _) => {
+                            reward += self.explode(
+                                center,
+                                shot,
+                                player.clone(),
+                                chest_vault.clone(),
+                                game_actions,
+                                profile,
+                            )?;
+                        }
+                        (Some((true, center)), Some(other)) => {
+                            reward += other.explode(
+                                center,
+                                shot,
+                                player.clone(),
+                                chest_vault.clone(),
+                                game_actions,
+                                profile,
+                            )?;
+                        }
+                        _ => {}
                     }
                 }
 
                 // 记录射击动作
                 let item = GameAction {
-                    action_id: self.action_id,
+                    action_id: self.header.action_id,
                     action_type: GAME_ACTION_SHIP_SHOT,
                     player: player.key(),
                     target: player.key(),
-                    damage: damage,
+                    damage,
                 };
                 self.add_new_game_action(game_actions, item);
             }
         }
 
-        Ok(())
+        Ok(reward)
     }
 
     // 添加新的游戏动作到历史记录
@@ -379,13 +1151,13 @@ impl GameDataAccount {
         game_action: GameAction,
     ) {
         {
-            let option_add = self.action_id.checked_add(1);
+            let option_add = self.header.action_id.checked_add(1);
             match option_add {
                 Some(val) => {
-                    self.action_id = val;
+                    self.header.action_id = val;
                 }
                 None => {
-                    self.action_id = 0;
+                    self.header.action_id = 0;
                 }
             }
         }
@@ -404,168 +1176,393 @@ impl GameDataAccount {
         }
     }
 
-    // 攻击指定格子
-    fn attack_tile<'info>(
+    // 爆破弹爆炸,攻击中心周围3×3范围内的格子,不会伤到射击者自己,返回击杀获得的SOL奖励
+    fn explode(
+        &mut self,
+        center: (usize, usize),
+        shot: Shot,
+        attacker: AccountInfo,
+        chest_vault: AccountInfo,
+        game_actions: &mut GameActionHistory,
+        profile: &mut PlayerProfile,
+    ) -> Result<u64> {
+        let (cx, cy) = center;
+        let mut reward = 0;
+        for x in cx.saturating_sub(1)..(cx + 2).min(self.width()) {
+            for y in cy.saturating_sub(1)..(cy + 2).min(self.height()) {
+                let tile = self.tile((x, y));
+                if tile.state == STATE_PLAYER && self.player_key(&tile) == *attacker.key {
+                    continue;
+                }
+                reward += self.attack_tile(
+                    (x, y),
+                    shot,
+                    attacker.clone(),
+                    chest_vault.clone(),
+                    game_actions,
+                    profile,
+                )?;
+            }
+        }
+        Ok(reward)
+    }
+
+    // 攻击指定格子,返回击杀获得的SOL奖励
+    //
+    // 团队模式中不会伤到队友
+    fn attack_tile(
         &mut self,
         attacked_position: (usize, usize),
-        damage: u64,
+        shot: Shot,
         attacker: AccountInfo,
         chest_vault: AccountInfo,
         game_actions: &mut GameActionHistory,
-        vault_token_account: &AccountInfo<'info>,
-        player_token_account: &AccountInfo<'info>,
-        token_account_owner_pda: &AccountInfo<'info>,
-        token_program: &AccountInfo<'info>,
-        token_owner_bump: u8,
-    ) -> Result<()> {
-        let mut attacked_tile: Tile = self.board[attacked_position.0][attacked_position.1];
+        profile: &mut PlayerProfile,
+    ) -> Result<u64> {
+        let mut attacked_tile: Tile = self.tile(attacked_position);
+        let damage = shot.damage;
+        let mut kill_reward = 0;
         msg!("Attack x:{} y:{}", attacked_position.0, attacked_position.1);
 
-        // 准备代币转账指令
-        let transfer_instruction = Transfer {
-            from: vault_token_account.to_account_info(),
-            to: player_token_account.to_account_info(),
-            authority: token_account_owner_pda.to_account_info(),
-        };
-
-        let seeds = &[b"token_account_owner_pda".as_ref(), &[token_owner_bump]];
-        let signer = &[&seeds[..]];
-
-        let cpi_ctx = CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            transfer_instruction,
-            signer,
-        );
-
         // 如果攻击目标是玩家
-        if attacked_tile.state == STATE_PLAYER {
-            let match_option = attacked_tile.health.checked_sub(damage);
+        if attacked_tile.state == STATE_PLAYER && !self.is_ally(shot.team, &attacked_tile) {
+            // 船只死亡时会释放玩家表中的位置,所以先取出目标公钥
+            let attacked_player = self.player_key(&attacked_tile);
+            let now = Clock::get()?.slot;
+            self.tile_mut(attacked_position).last_damage_slot = now;
+            let match_option = (attacked_tile.health as u64).checked_sub(damage);
             match match_option {
                 None => {
                     attacked_tile.health = 0;
-                    self.on_ship_died(attacked_position, attacked_tile, chest_vault, &attacker)?;
-                    // 转移摧毁船只的奖励代币
-                    anchor_spl::token::transfer(
-                        cpi_ctx,
-                        (attacked_tile.ship_level as u64)
-                            * DESTROY_SHIP_COIN_REWARD
-                            * TOKEN_DECIMAL_MULTIPLIER,
+                    let reward = self.on_ship_died(
+                        attacked_position,
+                        attacked_tile,
+                        chest_vault,
+                        &attacker,
                     )?;
-
-                    // 记录收集金币动作
-                    let new_game_action = GameAction {
-                        action_id: self.action_id,
-                        action_type: GAME_ACTION_SHIP_COINS_COLLECTED,
-                        player: attacker.key(),
-                        target: attacked_tile.player.key(),
-                        damage: DESTROY_SHIP_COIN_REWARD,
+                    let kill = Kill {
+                        team: shot.team,
+                        target: attacked_player,
+                        ship_level: attacked_tile.ship_level,
+                        reward,
                     };
-                    self.add_new_game_action(game_actions, new_game_action);
+                    self.record_kill(profile, kill, game_actions);
+                    kill_reward = reward;
                 }
                 Some(value) => {
                     msg!("New health {}", value);
-                    attacked_tile.health = value;
+                    attacked_tile.health = value as u32;
+                    self.tile_mut(attacked_position).health = value as u32;
+                    // 链弹把目标的上次移动记录推到将来,目标在 CHAIN_SHOT_PIN_SLOTS 内不能移动,
+                    // 同时降低目标的移动速度
+                    if shot.ammo == AMMO_CHAIN_SHOT {
+                        let tile = self.tile_mut(attacked_position);
+                        tile.last_move_slot = tile.last_move_slot.max(now + CHAIN_SHOT_PIN_SLOTS);
+                        tile.speed = tile.speed.saturating_sub(CHAIN_SHOT_SPEED_PENALTY).max(1);
+                    }
                     if value == 0 {
-                        self.on_ship_died(
+                        let reward = self.on_ship_died(
                             attacked_position,
                             attacked_tile,
                             chest_vault,
                             &attacker,
                         )?;
-                        // 转移摧毁船只的奖励代币
-                        anchor_spl::token::transfer(
-                            cpi_ctx,
-                            (attacked_tile.ship_level as u64)
-                                * DESTROY_SHIP_COIN_REWARD
-                                * TOKEN_DECIMAL_MULTIPLIER,
-                        )?;
-                        let item = GameAction {
-                            action_id: self.action_id,
-                            action_type: GAME_ACTION_SHIP_COINS_COLLECTED,
-                            player: attacker.key(),
-                            target: attacked_tile.player.key(),
-                            damage: DESTROY_SHIP_COIN_REWARD,
+                        let kill = Kill {
+                            team: shot.team,
+                            target: attacked_player,
+                            ship_level: attacked_tile.ship_level,
+                            reward,
                         };
-                        self.add_new_game_action(game_actions, item);
+                        self.record_kill(profile, kill, game_actions);
+                        kill_reward = reward;
                     }
                 }
             };
             // 记录受到伤害动作
             let item = GameAction {
-                action_id: self.action_id,
+                action_id: self.header.action_id,
                 action_type: GAME_ACTION_SHIP_TAKEN_DAMAGE,
                 player: attacker.key(),
-                target: attacked_tile.player.key(),
+                target: attacked_player,
                 damage,
             };
             self.add_new_game_action(game_actions, item);
         }
-        Ok(())
+        Ok(kill_reward)
     }
 
-    // 处理船只死亡
-    fn on_ship_died(
+    // 记录玩家击沉一艘船只: 击杀统计、队伍得分、摧毁船只的奖励金币及对应的动作
+    fn record_kill(
         &mut self,
-        attacked_position: (usize, usize),
-        attacked_tile: Tile,
-        chest_vault: AccountInfo,
-        attacker: &AccountInfo,
-    ) -> Result<()> {
-        msg!(
+        profile: &mut PlayerProfile,
+        kill: Kill,
+        game_actions: &mut GameActionHistory,
+    ) {
+        profile.record_kill(kill.reward);
+        self.add_team_score(kill.team);
+        // 记入摧毁船只的奖励金币
+        profile.accrue_gold((kill.ship_level as u64) * DESTROY_SHIP_COIN_REWARD);
+
+        // 记录收集金币动作
+        let item = GameAction {
+            action_id: self.header.action_id,
+            action_type: GAME_ACTION_SHIP_COINS_COLLECTED,
+            player: profile.owner,
+            target: kill.target,
+            damage: DESTROY_SHIP_COIN_REWARD,
+        };
+        self.add_new_game_action(game_actions, item);
+    }
+
+    // 团队模式中格子上的船只是否属于指定队伍,不属于任何队伍时没有队友
+    fn is_ally(&self, team: u16, tile: &Tile) -> bool {
+        self.header.team_mode() && team != 0 && tile.team == team
+    }
+
+    // 团队模式中为队伍加一分,记分的队伍已满时不再记录新的队伍
+    fn add_team_score(&mut self, team: u16) {
+        if !self.header.team_mode() || team == 0 {
+            return;
+        }
+        let scores = &mut self.header.scores;
+        let entry = match scores.iter().position(|entry| entry.team == team) {
+            Some(index) => Some(index),
+            None => scores.iter().position(|entry| entry.team == 0),
+        };
+        if let Some(index) = entry {
+            scores[index].team = team;
+            scores[index].score += 1;
+        }
+    }
+
+    /// 团队模式中击杀者附近的队友,按玩家表查找,与击杀者分享击杀奖励
+    ///
+    /// # 参数
+    /// * `player` - 击杀者公钥
+    /// * `neighbour` - 相邻区块,传入时一并查找其中的队友
+    ///
+    /// # 返回值
+    /// * `Vec<Pubkey>` - 与击杀者属于同一队伍且曼哈顿距离不超过 TEAM_SHARE_RADIUS 的队友
+    pub fn nearby_teammates(&self, player: &Pubkey, neighbour: Option<&GameBoard>) -> Vec<Pubkey> {
+        let Ok(position) = self.find_player_position(player) else {
+            return Vec::new();
+        };
+        let team = self.tile(position).team;
+        let origin = (position.0 as i64, position.1 as i64);
+        let mut boards = vec![(self, (0, 0))];
+        if let Some(other) = neighbour {
+            if let Some(offset) = self.neighbour_origin(other) {
+                boards.push((other, offset));
+            }
+        }
+
+        let mut teammates = Vec::new();
+        for (board, (dx, dy)) in boards {
+            let players = board.header.players;
+            for slot in players.iter().filter(|slot| slot.player != *player) {
+                let Ok(other) = board.find_player_position(&slot.player) else {
+                    continue;
+                };
+                let distance =
+                    origin.0.abs_diff(other.0 as i64 + dx) + origin.1.abs_diff(other.1 as i64 + dy);
+                if self.is_ally(team, &board.tile(other)) && distance <= TEAM_SHARE_RADIUS as u64 {
+                    teammates.push(slot.player);
+                }
+            }
+        }
+        teammates
+    }
+
+    /// 本次指令中被玩家击沉的船主,射击和移动指令据此发放悬赏
+    pub fn sunk(&self) -> &[Pubkey] {
+        &self.sunk
+    }
+
+    /// 把已经转入宝箱金库的SOL托管在玩家的船上,船只被击沉时归击沉者所有
+    pub fn escrow_reward(&mut self, player: &Pubkey, lamports: u64) -> Result<()> {
+        let position = self.find_player_position(player)?;
+        self.tile_mut(position).collect_reward += lamports;
+        Ok(())
+    }
+
+    // 处理船只死亡,返回攻击者获得的SOL奖励
+    //
+    // 同时记录被击沉的船主,悬赏在指令中按传入的悬赏账户发放
+    fn on_ship_died(
+        &mut self,
+        attacked_position: (usize, usize),
+        attacked_tile: Tile,
+        chest_vault: AccountInfo,
+        attacker: &AccountInfo,
+    ) -> Result<u64> {
+        msg!(
             "Enemy killed x:{} y:{} pubkey: {}",
             attacked_position.0,
             attacked_position.1,
-            attacked_tile.player
+            self.player_key(&attacked_tile)
         );
-        self.board[attacked_position.0][attacked_position.1].state = STATE_EMPTY;
-        // 转移奖励金额
-        **chest_vault.try_borrow_mut_lamports()? -= attacked_tile.collect_reward;
-        **attacker.try_borrow_mut_lamports()? += attacked_tile.collect_reward;
-        Ok(())
+        self.sunk.push(self.player_key(&attacked_tile));
+        let wreck_reward = self.leave_wreck(attacked_position, attacked_tile, true);
+        // 转移奖励金额,留在残骸中的部分仍然托管在金库中
+        let reward = attacked_tile.collect_reward - wreck_reward;
+        **chest_vault.try_borrow_mut_lamports()? -= reward;
+        **attacker.try_borrow_mut_lamports()? += reward;
+        Ok(reward)
+    }
+
+    // 移除被击沉的船只,按残骸比例在原地留下残骸宝箱,返回留在金库中的SOL奖励
+    //
+    // 残骸宝箱的伤害值字段记录船载大炮中会在结算时损失的部分(SHIP_SINK_LOSS_PERCENT),
+    // 收集残骸的玩家获得这些大炮,船主则在下次出海或打捞时销毁同样数量的大炮。
+    // 只有被玩家击沉的玩家船只才在残骸中留下大炮: NPC船只没有船载金库,
+    // 克苏鲁、NPC和风暴击沉的船只的大炮直接沉入海底。
+    // 大逃杀中不留残骸,托管的奖励全部进入奖池
+    fn leave_wreck(&mut self, position: (usize, usize), tile: Tile, by_player: bool) -> u64 {
+        self.remove_ship(position);
+        if self.header.royale == ROYALE_RUNNING {
+            self.header.royale_pot += tile.collect_reward;
+            return tile.collect_reward;
+        }
+        let percent = self.header.wreck_percent as u64;
+        if percent == 0 {
+            return 0;
+        }
+
+        let reward = tile.collect_reward * percent / 100;
+        let cannons = if by_player && tile.npc == NPC_NONE {
+            tile.damage as u64 * SHIP_SINK_LOSS_PERCENT / 100
+        } else {
+            0
+        };
+        self.place_chest(position, CHEST_TIER_COMMON);
+        let wreck = self.tile_mut(position);
+        wreck.collect_reward = reward;
+        wreck.damage = cannons as u32;
+        msg!(
+            "Wreck left at {} {} with {} lamports and {} cannons",
+            position.0,
+            position.1,
+            reward,
+            cannons
+        );
+        reward
     }
 
     // 移动指定玩家的船只
-    pub fn move_in_direction<'info>(
+    pub fn move_in_direction(
         &mut self,
         direction: u8,
-        player: AccountInfo,
-        chest_vault: AccountInfo,
-        vault_token_account: AccountInfo<'info>,
-        player_token_account: AccountInfo<'info>,
-        token_account_owner_pda: AccountInfo<'info>,
-        token_program: AccountInfo<'info>,
-        token_owner_bump: u8,
-        game_actions: &mut GameActionHistory,
+        ctx: MoveContext,
+        neighbour: Option<&mut GameBoard>,
     ) -> Result<()> {
-        // 1. 找到玩家当前位置
-        let current_pos = self.find_player_position(player.key)?;
+        // 1. 找到玩家当前位置,检查移动冷却时间
+        let current_pos = self.find_player_position(ctx.player.key)?;
+        self.start_move_cooldown(current_pos, ctx.game_actions.move_cooldown_slots)?;
+
+        // 2. 计算新位置,越过边缘时驶入相邻区块
+        let new_pos = match (self.calculate_new_position(current_pos, direction), neighbour) {
+            (Ok(new_pos), _) => new_pos,
+            (Err(_), Some(neighbour)) => {
+                return self.sail_to_neighbour(current_pos, direction, neighbour);
+            }
+            (Err(err), None) => return Err(err),
+        };
+
+        // 3. 处理移动逻辑
+        self.handle_movement(current_pos, new_pos, direction, ctx)
+    }
+
+    /// 修理船只
+    ///
+    /// # 参数
+    /// * `player` - 玩家公钥
+    /// * `rum` - 最多消耗的朗姆酒数量,每瓶恢复1点生命值
+    /// * `game_actions` - 游戏动作历史,记录修理动作
+    ///
+    /// # 返回值
+    /// * `Result<u64>` - 实际消耗的朗姆酒数量,生命值最多恢复到初始生命值
+    ///
+    /// # 功能说明
+    /// 停靠在港口旁边时可以随时修理,否则船只至少 REPAIR_SAFE_SLOTS 个slot没有受到伤害才能修理
+    pub fn repair(
+        &mut self,
+        player: &Pubkey,
+        rum: u64,
+        game_actions: &mut GameActionHistory,
+    ) -> Result<u64> {
+        let position = self.find_player_position(player)?;
+        let tile = self.tile(position);
+
+        let missing = tile.start_health.saturating_sub(tile.health) as u64;
+        let used = rum.min(missing);
+        if used == 0 {
+            return Err(SevenSeasError::NothingToRepair.into());
+        }
 
-        // 2. 计算新位置
+        let now = Clock::get()?.slot;
+        if !self.is_docked(position)
+            && !cooldown_ready(tile.last_damage_slot, REPAIR_SAFE_SLOTS, now)
+        {
+            return Err(SevenSeasError::RepairNotAllowed.into());
+        }
+
+        self.tile_mut(position).health = tile.health + used as u32;
+
+        // 记录修理动作
+        let item = GameAction {
+            action_id: self.header.action_id,
+            action_type: GAME_ACTION_SHIP_REPAIRED,
+            player: *player,
+            target: *player,
+            damage: used,
+        };
+        self.add_new_game_action(game_actions, item);
+
+        msg!("Repaired {} health at {} {}", used, position.0, position.1);
+        Ok(used)
+    }
+
+    // 船只是否停靠在港口旁边
+    fn is_docked(&self, position: (usize, usize)) -> bool {
+        let (x, y) = position;
+        (x > 0 && self.tile((x - 1, y)).state == STATE_PORT)
+            || (x + 1 < self.width() && self.tile((x + 1, y)).state == STATE_PORT)
+            || (y > 0 && self.tile((x, y - 1)).state == STATE_PORT)
+            || (y + 1 < self.height() && self.tile((x, y + 1)).state == STATE_PORT)
+    }
+
+    // 只在棋盘内移动到空格子
+    //
+    // 目标格子是宝箱或其他船只时需要发放奖励或结算攻击,必须使用完整的移动指令
+    pub fn move_lite(
+        &mut self,
+        direction: u8,
+        player: &Pubkey,
+        game_actions: &GameActionHistory,
+    ) -> Result<()> {
+        let current_pos = self.find_player_position(player)?;
+        self.start_move_cooldown(current_pos, game_actions.move_cooldown_slots)?;
         let new_pos = self.calculate_new_position(current_pos, direction)?;
 
-        // 3. 处理移动逻辑
-        self.handle_movement(
-            current_pos,
-            new_pos,
-            direction,
-            player,
-            chest_vault,
-            vault_token_account,
-            player_token_account,
-            token_account_owner_pda,
-            token_program,
-            token_owner_bump,
-            game_actions,
-        )
+        match self.tile(new_pos).state {
+            STATE_EMPTY => self.move_to_empty_tile(current_pos, new_pos, direction),
+            STATE_CHEST | STATE_PLAYER => Err(SevenSeasError::DestinationRequiresFullMove.into()),
+            STATE_ISLAND | STATE_REEF | STATE_PORT | STATE_STORM => {
+                Err(SevenSeasError::TileIsBlocked.into())
+            }
+            _ => Err(SevenSeasError::InvalidTileState.into()),
+        }
     }
 
-    // 查找玩家位置
+    // 查找玩家位置,从玩家表中读取坐标
     fn find_player_position(&self, player_key: &Pubkey) -> Result<(usize, usize)> {
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                let tile = &self.board[x][y];
-                if tile.state == STATE_PLAYER && tile.player == *player_key {
-                    return Ok((x, y));
+        if let Some(index) = self.find_player_slot(player_key) {
+            let slot = self.header.players[index as usize];
+            let position = (slot.x as usize, slot.y as usize);
+            if position.0 < self.width() && position.1 < self.height() {
+                let tile = self.tile(position);
+                if tile.state == STATE_PLAYER && tile.player == index {
+                    return Ok(position);
                 }
             }
         }
@@ -581,29 +1578,63 @@ impl GameDataAccount {
         let (x, y) = current;
         match direction {
             0 if y > 0 => Ok((x, y - 1)),
-            1 if x < BOARD_SIZE_X - 1 => Ok((x + 1, y)),
-            2 if y < BOARD_SIZE_Y - 1 => Ok((x, y + 1)),
+            1 if x < self.width() - 1 => Ok((x + 1, y)),
+            2 if y < self.height() - 1 => Ok((x, y + 1)),
             3 if x > 0 => Ok((x - 1, y)),
             _ => Err(SevenSeasError::WrongDirectionInput.into()),
         }
     }
 
+    // 驶出棋盘边缘,进入相邻区块
+    fn sail_to_neighbour(
+        &mut self,
+        current_pos: (usize, usize),
+        direction: u8,
+        neighbour: &mut GameBoard,
+    ) -> Result<()> {
+        let (x, y) = (current_pos.0 as i64, current_pos.1 as i64);
+        let (x, y) = match direction {
+            0 => (x, y - 1),
+            1 => (x + 1, y),
+            2 => (x, y + 1),
+            3 => (x - 1, y),
+            _ => return Err(SevenSeasError::WrongDirectionInput.into()),
+        };
+        if self.neighbour_offset(neighbour).is_none() {
+            return Err(SevenSeasError::NotANeighbourChunk.into());
+        }
+        let new_pos = self
+            .position_in_neighbour(neighbour, x, y)
+            .ok_or(SevenSeasError::TileOutOfBounds)?;
+        if neighbour.tile(new_pos).state != STATE_EMPTY {
+            return Err(SevenSeasError::TileIsBlocked.into());
+        }
+
+        // 在相邻区块的玩家表中登记,并释放本区块的位置
+        let mut tile = self.tile(current_pos);
+        let slot = self.header.players[tile.player as usize];
+        tile.player = neighbour.allocate_player_slot(slot.player, slot.avatar, new_pos)?;
+        tile.look_direction = direction;
+        *neighbour.tile_mut(new_pos) = tile;
+        self.remove_ship(current_pos);
+
+        msg!(
+            "Sailed into neighbouring chunk at {} {}",
+            new_pos.0,
+            new_pos.1
+        );
+        Ok(())
+    }
+
     // 处理移动逻辑
-    fn handle_movement<'info>(
+    fn handle_movement(
         &mut self,
         current_pos: (usize, usize),
         new_pos: (usize, usize),
         direction: u8,
-        player: AccountInfo,
-        chest_vault: AccountInfo,
-        vault_token_account: AccountInfo<'info>,
-        player_token_account: AccountInfo<'info>,
-        token_account_owner_pda: AccountInfo<'info>,
-        token_program: AccountInfo<'info>,
-        token_owner_bump: u8,
-        game_actions: &mut GameActionHistory,
+        ctx: MoveContext,
     ) -> Result<()> {
-        let new_tile = &self.board[new_pos.0][new_pos.1];
+        let new_tile = self.tile(new_pos);
 
         match new_tile.state {
             STATE_EMPTY => {
@@ -612,40 +1643,158 @@ impl GameDataAccount {
             }
             STATE_CHEST => {
                 // 收集宝箱
-                self.collect_chest(
-                    current_pos,
-                    new_pos,
-                    direction,
-                    player,
-                    chest_vault,
-                    vault_token_account,
-                    player_token_account,
-                    token_account_owner_pda,
-                    token_program,
-                    token_owner_bump,
-                    game_actions,
-                )
+                self.collect_chest(current_pos, new_pos, direction, ctx)
             }
 
             STATE_PLAYER => {
-                // 攻击其他玩家
-                self.attack_tile(
-                    new_pos,
-                    1,
-                    player,
-                    chest_vault,
-                    game_actions,
-                    &vault_token_account,
-                    &player_token_account,
-                    &token_account_owner_pda,
-                    &token_program,
-                    token_owner_bump,
-                )
+                // 团队模式中不能撞向队友
+                if self.is_ally(self.tile(current_pos).team, &new_tile) {
+                    return Err(SevenSeasError::AlliedShip.into());
+                }
+                // 撞上其他玩家时发起接舷战
+                self.board_ship(current_pos, new_pos, ctx)
+            }
+            STATE_ISLAND | STATE_REEF | STATE_PORT | STATE_STORM => {
+                // 岛屿、暗礁、港口和风暴不可通行
+                Err(SevenSeasError::TileIsBlocked.into())
             }
             _ => Err(SevenSeasError::InvalidTileState.into()),
         }
     }
 
+    // 接舷战
+    //
+    // 双方按生命值、船员(大炮数量)、船只等级和随机数计算战力,平局时守方获胜。
+    // 胜者夺取败者一部分托管的击杀奖励,奖励仍然留在宝箱金库中,击沉胜者的玩家会一并获得。
+    // 败者受到 BOARDING_DAMAGE 的伤害,被击沉时其余托管奖励也归胜者;
+    // 胜者还会接管败者船上尚未领取的宝箱金币,NPC没有玩家档案,不参与接管。
+    // 玩家胜者击沉败者时与射击击沉一样记录击杀。
+    // 发起方无论胜负都受到 BOARDING_RECOIL_DAMAGE 的伤害
+    fn board_ship(
+        &mut self,
+        attacker_pos: (usize, usize),
+        defender_pos: (usize, usize),
+        ctx: MoveContext,
+    ) -> Result<()> {
+        // 守方是玩家的船只时必须传入船主的档案
+        let defender = self.tile(defender_pos);
+        let defender_profile = if defender.npc == NPC_NONE {
+            let owner = self.player_key(&defender);
+            Some(
+                ctx.target_profile
+                    .filter(|target| target.owner == owner)
+                    .ok_or(SevenSeasError::MissingTargetProfile)?,
+            )
+        } else {
+            None
+        };
+
+        let mut rng = XorShift64::new(Clock::get()?.slot ^ self.header.action_id);
+        let mut strength = |tile: &Tile| {
+            tile.health as u64
+                + tile.damage as u64 * BOARDING_CREW_WEIGHT
+                + tile.ship_level as u64 * BOARDING_LEVEL_WEIGHT
+                + rng.next() % BOARDING_RANDOMNESS
+        };
+        let attacker_strength = strength(&self.tile(attacker_pos));
+        let defender_strength = strength(&defender);
+
+        let attacker_won = attacker_strength > defender_strength;
+        let (winner_pos, loser_pos) = if attacker_won {
+            (attacker_pos, defender_pos)
+        } else {
+            (defender_pos, attacker_pos)
+        };
+        let winner = self.player_key(&self.tile(winner_pos));
+        let loser = self.player_key(&self.tile(loser_pos));
+        let captured = self.tile(loser_pos).collect_reward * BOARDING_CAPTURE_PERCENT / 100;
+        self.tile_mut(loser_pos).collect_reward -= captured;
+        self.tile_mut(winner_pos).collect_reward += captured;
+
+        // 胜者接管败者船上尚未领取的金币
+        let (mut winner_profile, loser_profile) = if attacker_won {
+            (Some(ctx.profile), defender_profile)
+        } else {
+            (defender_profile, Some(ctx.profile))
+        };
+        if let (Some(winner_profile), Some(loser_profile)) =
+            (winner_profile.as_deref_mut(), loser_profile)
+        {
+            let gold = std::mem::take(&mut loser_profile.pending_gold);
+            winner_profile.accrue_gold(gold);
+            msg!("Boarding winner took over {} gold", gold);
+        }
+
+        // 败者受到伤害,发起方无论胜负都会受损
+        let now = Clock::get()?.slot;
+        let winner_team = self.tile(winner_pos).team;
+        let loser_level = self.tile(loser_pos).ship_level;
+        let loser_sunk = self.boarding_damage(loser_pos, BOARDING_DAMAGE, Some(winner_pos), now);
+        // 被玩家击沉时与射击一样记录击杀,并记录被击沉的船主用于发放悬赏。
+        // 夺取的奖励仍然托管在胜者的船上,不计入SOL收益
+        if let (true, Some(winner_profile)) = (loser_sunk, winner_profile) {
+            self.sunk.push(loser);
+            let kill = Kill {
+                team: winner_team,
+                target: loser,
+                ship_level: loser_level,
+                reward: 0,
+            };
+            self.record_kill(winner_profile, kill, &mut *ctx.game_actions);
+        }
+        if !(loser_sunk && loser_pos == attacker_pos) {
+            self.boarding_damage(attacker_pos, BOARDING_RECOIL_DAMAGE, None, now);
+        }
+
+        // 记录接舷战动作
+        let item = GameAction {
+            action_id: self.header.action_id,
+            action_type: GAME_ACTION_SHIP_BOARDED,
+            player: winner,
+            target: loser,
+            damage: captured,
+        };
+        self.add_new_game_action(ctx.game_actions, item);
+
+        msg!(
+            "Boarding {} vs {}, winner at {} {} captured {}",
+            attacker_strength,
+            defender_strength,
+            winner_pos.0,
+            winner_pos.1,
+            captured
+        );
+        Ok(())
+    }
+
+    // 接舷战中对船只造成伤害,返回船只是否被击沉
+    //
+    // 被对方击沉时,没有留在残骸中的托管奖励归对方所有,仍然留在宝箱金库中
+    fn boarding_damage(
+        &mut self,
+        position: (usize, usize),
+        damage: u64,
+        winner_pos: Option<(usize, usize)>,
+        now: u64,
+    ) -> bool {
+        let tile = self.tile(position);
+        let health = (tile.health as u64).saturating_sub(damage) as u32;
+        let ship = self.tile_mut(position);
+        ship.health = health;
+        ship.last_damage_slot = now;
+        if health > 0 {
+            return false;
+        }
+
+        let by_player = winner_pos.is_some_and(|winner| self.tile(winner).npc == NPC_NONE);
+        let wreck_reward = self.leave_wreck(position, tile, by_player);
+        if let Some(winner) = winner_pos {
+            self.tile_mut(winner).collect_reward += tile.collect_reward - wreck_reward;
+        }
+        msg!("Ship sunk while boarding at {} {}", position.0, position.1);
+        true
+    }
+
     // 移动到空格子
     fn move_to_empty_tile(
         &mut self,
@@ -654,60 +1803,66 @@ impl GameDataAccount {
         direction: u8,
     ) -> Result<()> {
         // 移动玩家
-        self.board[new_pos.0][new_pos.1] = self.board[current_pos.0][current_pos.1];
-        self.board[current_pos.0][current_pos.1].state = STATE_EMPTY;
-        self.board[new_pos.0][new_pos.1].look_direction = direction;
+        self.move_ship(current_pos, new_pos, direction);
         msg!("Moved player to new tile");
         Ok(())
     }
 
     // 收集宝箱
-    fn collect_chest<'info>(
+    //
+    // 有掉落表时按宝箱等级发放金币,并随机掉落一个物品;没有掉落表时只发放固定的金币奖励
+    fn collect_chest(
         &mut self,
         current_pos: (usize, usize),
         new_pos: (usize, usize),
         direction: u8,
-        player: AccountInfo,
-        chest_vault: AccountInfo,
-        vault_token_account: AccountInfo<'info>,
-        player_token_account: AccountInfo<'info>,
-        token_account_owner_pda: AccountInfo<'info>,
-        token_program: AccountInfo<'info>,
-        token_owner_bump: u8,
-        game_actions: &mut GameActionHistory,
+        ctx: MoveContext,
     ) -> Result<()> {
-        let chest_reward = self.board[new_pos.0][new_pos.1].collect_reward;
+        let MoveContext {
+            player,
+            chest_vault,
+            profile,
+            game_actions,
+            loot_table,
+            ..
+        } = ctx;
+        let chest = self.tile(new_pos);
+        let chest_reward = chest.collect_reward;
+        // 残骸宝箱中的大炮,普通宝箱为0
+        let chest_cannons = chest.damage as u64;
+        let team = self.tile(current_pos).team;
 
         // 移动玩家
-        self.board[new_pos.0][new_pos.1] = self.board[current_pos.0][current_pos.1];
-        self.board[current_pos.0][current_pos.1].state = STATE_EMPTY;
-        self.board[new_pos.0][new_pos.1].look_direction = direction;
+        self.move_ship(current_pos, new_pos, direction);
+        self.add_team_score(team);
 
         // 转移SOL奖励
         **chest_vault.try_borrow_mut_lamports()? -= chest_reward;
         **player.try_borrow_mut_lamports()? += chest_reward;
 
-        // 转移代币奖励
-        let transfer_instruction = Transfer {
-            from: vault_token_account,
-            to: player_token_account,
-            authority: token_account_owner_pda,
+        // 记入金币奖励,通过claim_rewards领取
+        profile.record_chest(chest_reward);
+        profile.accrue_loot(LOOT_ITEM_CANNON, chest_cannons);
+        let gold = match loot_table {
+            Some(table) => {
+                let mut rng = XorShift64::new(Clock::get()?.slot ^ self.header.action_id);
+                if let Some(entry) = table.roll_loot(chest.chest_tier, rng.next()) {
+                    profile.accrue_loot(entry.item, entry.amount);
+                    msg!("Looted {} of item {}", entry.amount, entry.item);
+                }
+                table.gold(chest.chest_tier)
+            }
+            None => CHEST_COIN_REWARD,
         };
-
-        let seeds = &[b"token_account_owner_pda".as_ref(), &[token_owner_bump]];
-        let signer = &[&seeds[..]];
-
-        let cpi_ctx = CpiContext::new_with_signer(token_program, transfer_instruction, signer);
-
-        anchor_spl::token::transfer(cpi_ctx, CHEST_COIN_REWARD * TOKEN_DECIMAL_MULTIPLIER)?;
+        profile.accrue_gold(gold);
 
         // 记录收集金币动作
         let action = GameAction {
-            action_id: self.action_id,
+            action_id: self.header.action_id,
             action_type: GAME_ACTION_SHIP_COINS_COLLECTED,
             player: player.key(),
             target: player.key(),
-            damage: CHEST_COIN_REWARD,
+            damage: gold,
         };
         self.add_new_game_action(game_actions, action);
 
@@ -716,12 +1871,7 @@ impl GameDataAccount {
     }
 
     pub fn clear(&mut self) -> Result<()> {
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                self.board[x][y].state = STATE_EMPTY;
-            }
-        }
-        Ok(())
+        self.reset()
     }
 
     /// 生成玩家到游戏棋盘上
@@ -731,78 +1881,86 @@ impl GameDataAccount {
     /// * `avatar` - 玩家头像公钥
     /// * `ship` - 玩家船只信息
     /// * `extra_health` - 额外生命值
+    /// * `guild` - 玩家所属的公会编号,0表示不属于任何公会
     ///
     /// # 返回值
     /// * `Result<()>` - 成功返回Ok(()),失败返回错误
     ///
     /// # 功能说明
-    /// 1. 遍历棋盘找出所有空格子
-    /// 2. 检查玩家是否已存在
-    /// 3. 随机选择一个空格子生成玩家
-    /// 4. 根据船只等级设置攻击范围
+    /// 1. 检查玩家是否已存在,以及大逃杀是否已经开始
+    /// 2. 统计棋盘上的空格子数量
+    /// 3. 随机选择一个空格子生成玩家,并在玩家表中登记
+    /// 4. 根据船型和升级次数设置攻击范围和移动速度
+    /// 5. 团队模式中以公会编号作为船只的队伍编号
     pub fn spawn_player(
         &mut self,
         player: AccountInfo,
         avatar: Pubkey,
         ship: &mut Ship,
         extra_health: u64,
+        guild: u16,
     ) -> Result<()> {
-        // 存储所有空格子的坐标
-        let mut empty_slots: Vec<(usize, usize)> = Vec::new();
+        // 如果玩家已存在则返回错误
+        if self.find_player_slot(player.key).is_some() {
+            return Err(SevenSeasError::PlayerAlreadyExists.into());
+        }
 
-        // 遍历棋盘找出所有空格子
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                let tile = self.board[x][y];
-                if tile.state == STATE_EMPTY {
-                    empty_slots.push((x, y));
-                } else if tile.player == player.key.clone() && tile.state == STATE_PLAYER {
-                    // 如果玩家已存在则返回错误
-                    return Err(SevenSeasError::PlayerAlreadyExists.into());
-                }
-            }
+        // 大逃杀开始后不能再出海
+        if self.header.royale == ROYALE_RUNNING {
+            return Err(SevenSeasError::RoyaleInProgress.into());
         }
 
+        // 统计空格子数量
+        let empty_tiles = self.count_empty_tiles();
+
         // 如果没有空格子则返回错误
-        if empty_slots.is_empty() {
+        if empty_tiles == 0 {
             return Err(SevenSeasError::BoardIsFull.into());
         }
 
         // 创建随机数生成器
         let mut rng = XorShift64 {
-            a: empty_slots.len() as u64,
+            a: empty_tiles as u64,
         };
 
         // 随机选择一个空格子
-        let random_empty_slot = empty_slots[(rng.next() % (empty_slots.len() as u64)) as usize];
+        let random_empty_slot = self
+            .nth_empty_tile((rng.next() % empty_tiles as u64) as usize)
+            .ok_or(SevenSeasError::BoardIsFull)?;
         msg!(
             "Player spawn at {} {}",
             random_empty_slot.0,
             random_empty_slot.1
         );
 
-        // 根据船只等级设置攻击范围
-        let range = match ship.upgrades {
-            0 | 1 | 2 => {
-                1 // 0-2级船只攻击范围为1
-            }
-            _ => {
-                2 // 3级及以上船只攻击范围为2
-            }
-        };
+        // 根据船型设置攻击范围和移动速度
+        let stats = ship.class_stats().ok_or(SevenSeasError::InvalidShipClass)?;
+        let range = ship.range().ok_or(SevenSeasError::InvalidShipClass)?;
+
+        // 在玩家表中登记玩家
+        let player_index = self.allocate_player_slot(*player.key, avatar, random_empty_slot)?;
 
         // 在选中的格子生成玩家
-        self.board[random_empty_slot.0][random_empty_slot.1] = Tile {
-            player: player.key.clone(),
-            avatar: avatar.clone(),
+        let health = saturate_u32(ship.health + extra_health);
+        *self.tile_mut(random_empty_slot) = Tile {
+            player: player_index,
             state: STATE_PLAYER,
-            health: ship.health + extra_health,
-            start_health: ship.health + extra_health,
-            damage: ship.cannons,
+            health,
+            start_health: health,
+            damage: saturate_u32(ship.cannons),
             range,
             collect_reward: PLAYER_KILL_REWARD,
             look_direction: 0,
-            ship_level: ship.upgrades,
+            ship_level: u8::try_from(ship.upgrades).unwrap_or(u8::MAX),
+            speed: stats.move_speed,
+            last_shot_slot: 0,
+            last_move_slot: 0,
+            last_damage_slot: 0,
+            chest_tier: 0,
+            npc: NPC_NONE,
+            home_x: 0,
+            home_y: 0,
+            team: if self.header.team_mode() { guild } else { 0 },
         };
 
         Ok(())
@@ -811,42 +1969,33 @@ impl GameDataAccount {
     /// 在游戏棋盘上生成宝箱
     ///
     /// # 参数
-    /// * `player` - 玩家账户信息
+    /// * `loot_table` - 掉落表,用于选择宝箱等级,没有时生成普通宝箱
     ///
     /// # 返回值
     /// * `Result<()>` - 成功返回Ok(()),失败返回错误
     ///
     /// # 功能说明
-    /// 1. 遍历棋盘找出所有空格子
+    /// 1. 统计棋盘上的空格子数量
     /// 2. 随机选择一个空格子生成宝箱
-    pub fn spawn_chest(&mut self, player: AccountInfo) -> Result<()> {
-        // 存储所有空格子的坐标
-        let mut empty_slots = Vec::new();
-
-        // 遍历棋盘找出所有空格子
-        for x in 0..BOARD_SIZE_X {
-            for y in 0..BOARD_SIZE_Y {
-                let tile = self.board[x][y];
-                if tile.state == STATE_EMPTY {
-                    empty_slots.push((x, y));
-                } else {
-                    //msg!("{}", tile.player);
-                }
-            }
-        }
+    /// 3. 按掉落表的生成权重选择宝箱等级
+    pub fn spawn_chest(&mut self, loot_table: Option<&LootTable>) -> Result<()> {
+        // 统计空格子数量
+        let empty_tiles = self.count_empty_tiles();
 
         // 如果没有空格子则返回错误
-        if empty_slots.len() == 0 {
+        if empty_tiles == 0 {
             return Err(SevenSeasError::BoardIsFull.into());
         }
 
         // 创建随机数生成器
         let mut rng = XorShift64 {
-            a: (empty_slots.len() + 1) as u64,
+            a: (empty_tiles + 1) as u64,
         };
 
         // 随机选择一个空格子
-        let random_empty_slot = empty_slots[(rng.next() % (empty_slots.len() as u64)) as usize];
+        let random_empty_slot = self
+            .nth_empty_tile((rng.next() % empty_tiles as u64) as usize)
+            .ok_or(SevenSeasError::BoardIsFull)?;
         msg!(
             "Chest spawn at {} {}",
             random_empty_slot.0,
@@ -854,9 +2003,41 @@ impl GameDataAccount {
         );
 
         // 在选中的格子生成宝箱
-        self.board[random_empty_slot.0][random_empty_slot.1] = Tile {
-            player: player.key.clone(),
-            avatar: player.key.clone(),
+        let tier = loot_table.map_or(CHEST_TIER_COMMON, |table| table.roll_tier(rng.next()));
+        self.place_chest(random_empty_slot, tier);
+
+        Ok(())
+    }
+
+    /// 宝箱是否由 spawn_chests 维持,而不是由生成玩家时附带生成
+    pub fn chest_spawning_enabled(&self) -> bool {
+        self.header.max_chests > 0
+    }
+
+    /// 距离目标宝箱数量还差多少个宝箱
+    ///
+    /// 目标数量为水域格子数(包括宝箱和船只所在格子)乘以宝箱密度,且不超过最多宝箱数
+    pub fn missing_chests(&self) -> u64 {
+        let (mut chests, mut water) = (0u64, 0u64);
+        for tile in self.tiles.iter() {
+            match tile.state {
+                STATE_CHEST => {
+                    chests += 1;
+                    water += 1;
+                }
+                STATE_EMPTY | STATE_PLAYER => water += 1,
+                _ => {}
+            }
+        }
+        let target =
+            (water * self.header.chest_density as u64 / 1000).min(self.header.max_chests as u64);
+        target.saturating_sub(chests)
+    }
+
+    // 在指定格子放置指定等级的宝箱
+    fn place_chest(&mut self, position: (usize, usize), tier: u8) {
+        *self.tile_mut(position) = Tile {
+            player: 0,
             state: STATE_CHEST,
             health: 1,
             start_health: 1,
@@ -865,15 +2046,593 @@ impl GameDataAccount {
             collect_reward: CHEST_REWARD,
             look_direction: 0,
             ship_level: 0,
+            speed: 0,
+            last_shot_slot: 0,
+            last_move_slot: 0,
+            last_damage_slot: 0,
+            chest_tier: tier,
+            npc: NPC_NONE,
+            home_x: 0,
+            home_y: 0,
+            team: 0,
         };
+    }
+
+    /// 生成NPC船只
+    ///
+    /// # 参数
+    /// * `behaviour` - NPC行为,见 NPC_* 常量
+    /// * `class` - 船型,决定NPC的生命值、火力、射程和速度
+    ///
+    /// # 功能说明
+    /// 1. NPC和玩家的船只一样占用玩家表中的位置,公钥由位置索引生成
+    /// 2. 守护型NPC生成在一个随机宝箱旁边,并把宝箱作为守护点,没有宝箱时随机生成
+    /// 3. NPC托管 NPC_BOUNTY 的击沉奖励,击沉它的玩家通过 on_ship_died 获得
+    pub fn spawn_npc(&mut self, behaviour: u8, class: u8) -> Result<()> {
+        if !(NPC_PATROL..=NPC_GUARD).contains(&behaviour) {
+            return Err(SevenSeasError::InvalidNpcBehaviour.into());
+        }
+        let stats = SHIP_CLASSES
+            .get(class as usize)
+            .ok_or(SevenSeasError::InvalidShipClass)?;
+
+        let empty_tiles = self.count_empty_tiles();
+        if empty_tiles == 0 {
+            return Err(SevenSeasError::BoardIsFull.into());
+        }
+        let guard_tiles = if behaviour == NPC_GUARD {
+            self.guard_tiles().count()
+        } else {
+            0
+        };
+
+        let mut rng = XorShift64::new(Clock::get()?.slot ^ self.header.action_id);
+        let (position, home) = if guard_tiles > 0 {
+            self.guard_tiles()
+                .nth((rng.next() % guard_tiles as u64) as usize)
+                .ok_or(SevenSeasError::BoardIsFull)?
+        } else {
+            let position = self
+                .nth_empty_tile((rng.next() % empty_tiles as u64) as usize)
+                .ok_or(SevenSeasError::BoardIsFull)?;
+            (position, position)
+        };
+
+        let index = self
+            .find_player_slot(&Pubkey::default())
+            .ok_or(SevenSeasError::PlayerTableFull)?;
+        self.allocate_player_slot(Self::npc_key(index), Pubkey::default(), position)?;
+
+        let health = saturate_u32(stats.base_health);
+        *self.tile_mut(position) = Tile {
+            player: index,
+            state: STATE_PLAYER,
+            health,
+            start_health: health,
+            damage: saturate_u32(stats.cannon_slots / 2),
+            range: stats.range,
+            collect_reward: NPC_BOUNTY,
+            look_direction: 0,
+            ship_level: 0,
+            speed: stats.move_speed,
+            last_shot_slot: 0,
+            last_move_slot: 0,
+            last_damage_slot: 0,
+            chest_tier: 0,
+            npc: behaviour,
+            home_x: home.0 as u16,
+            home_y: home.1 as u16,
+            team: 0,
+        };
+
+        msg!("NPC {} spawn at {} {}", behaviour, position.0, position.1);
+        Ok(())
+    }
+
+    /// 所有NPC按各自的行为行动一次
+    ///
+    /// # 参数
+    /// * `game_actions` - 游戏动作历史,提供冷却时间并记录射击和伤害
+    ///
+    /// # 返回值
+    /// * `Result<u64>` - 本次行动的NPC数量
+    ///
+    /// # 功能说明
+    /// NPC遵守和玩家相同的冷却时间、射程和地形规则:
+    /// 1. 射击冷却结束且有玩家的船只在舷侧射程内时,转向并开炮
+    /// 2. 否则在移动冷却结束后按行为移动一格,只能驶入空格子
+    ///
+    /// NPC击沉的船只托管的奖励留在宝箱金库中,和克苏鲁一样
+    pub fn npc_tick(&mut self, game_actions: &mut GameActionHistory) -> Result<u64> {
+        let now = Clock::get()?.slot;
+        let mut acted = 0;
+
+        for index in 0..MAX_PLAYERS {
+            let slot = self.header.players[index];
+            if slot.player == Pubkey::default() {
+                continue;
+            }
+            let position = (slot.x as usize, slot.y as usize);
+            let tile = self.tile(position);
+            if tile.npc == NPC_NONE {
+                continue;
+            }
+
+            // 1. 射击
+            if cooldown_ready(tile.last_shot_slot, game_actions.shoot_cooldown_slots, now)
+                && self.npc_fire(position, now, game_actions)?
+            {
+                acted += 1;
+                continue;
+            }
+
+            // 2. 移动
+            let cooldown = game_actions.move_cooldown_slots / tile.speed.max(1) as u64;
+            if !cooldown_ready(tile.last_move_slot, cooldown, now) {
+                continue;
+            }
+            let home = (tile.home_x as usize, tile.home_y as usize);
+            let moved = match tile.npc {
+                NPC_PATROL => (0..4)
+                    .any(|turn| self.npc_move(position, (tile.look_direction + turn) % 4, now)),
+                NPC_HUNT => match self.nearest_player_ship(position, usize::MAX) {
+                    Some(target) => self.npc_step_toward(position, target, now),
+                    None => false,
+                },
+                NPC_GUARD => match self.nearest_player_ship(home, NPC_GUARD_RADIUS) {
+                    Some(target) => self.npc_step_toward(position, target, now),
+                    None if Self::manhattan_distance(position, home) > 1 => {
+                        self.npc_step_toward(position, home, now)
+                    }
+                    None => false,
+                },
+                _ => false,
+            };
+            if moved {
+                acted += 1;
+            }
+        }
+
+        msg!("{} NPCs acted", acted);
+        Ok(acted)
+    }
+
+    // NPC在玩家表中的公钥,由位置索引生成,不对应任何钱包
+    fn npc_key(index: u16) -> Pubkey {
+        let mut bytes = [0u8; 32];
+        bytes[..3].copy_from_slice(b"npc");
+        bytes[30..].copy_from_slice(&index.to_le_bytes());
+        Pubkey::new_from_array(bytes)
+    }
+
+    // 两个格子之间的曼哈顿距离
+    fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> usize {
+        a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+    }
+
+    // 在最远距离内离指定格子最近的玩家船只(不包括NPC)
+    fn nearest_player_ship(
+        &self,
+        from: (usize, usize),
+        max_distance: usize,
+    ) -> Option<(usize, usize)> {
+        (0..MAX_PLAYERS)
+            .map(|index| self.header.players[index])
+            .filter(|slot| slot.player != Pubkey::default())
+            .map(|slot| (slot.x as usize, slot.y as usize))
+            .filter(|position| self.tile(*position).npc == NPC_NONE)
+            .map(|position| (Self::manhattan_distance(from, position), position))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, position)| position)
+    }
+
+    // 沿一个方向在射程内能命中的玩家船只(不包括NPC),炮弹不能穿过岛屿和港口
+    fn broadside_targets(
+        &self,
+        position: (usize, usize),
+        (dx, dy): (i64, i64),
+        range: u8,
+    ) -> Vec<(usize, usize)> {
+        let mut targets = Vec::new();
+        for distance in 1..range as i64 + 1 {
+            let x = position.0 as i64 + dx * distance;
+            let y = position.1 as i64 + dy * distance;
+            if x < 0 || y < 0 || x as usize >= self.width() || y as usize >= self.height() {
+                break;
+            }
+            let target = (x as usize, y as usize);
+            let tile = self.tile(target);
+            if Self::blocks_shots(tile.state) {
+                break;
+            }
+            if tile.state == STATE_PLAYER && tile.npc == NPC_NONE {
+                targets.push(target);
+            }
+        }
+        targets
+    }
+
+    // NPC开炮,射程内没有目标时返回false
+    //
+    // 和玩家一样只能向舷侧开炮,先转向使舷侧对准目标所在的行或列
+    fn npc_fire(
+        &mut self,
+        position: (usize, usize),
+        now: u64,
+        game_actions: &mut GameActionHistory,
+    ) -> Result<bool> {
+        let tile = self.tile(position);
+        let horizontal = [(-1, 0), (1, 0)];
+        let vertical = [(0, 1), (0, -1)];
+        let has_targets = |directions: [(i64, i64); 2]| {
+            directions.iter().any(|direction| {
+                !self
+                    .broadside_targets(position, *direction, tile.range)
+                    .is_empty()
+            })
+        };
+
+        // 朝向为偶数时向左右两侧开炮,为奇数时向下上两侧开炮,已经对准时保持原来的朝向
+        let (directions, look_direction) = if has_targets(horizontal) {
+            let look_direction = match tile.look_direction {
+                0 | 2 => tile.look_direction,
+                _ => 0,
+            };
+            (horizontal, look_direction)
+        } else if has_targets(vertical) {
+            let look_direction = match tile.look_direction {
+                1 | 3 => tile.look_direction,
+                _ => 1,
+            };
+            (vertical, look_direction)
+        } else {
+            return Ok(false);
+        };
+
+        let npc = self.player_key(&tile);
+        let damage = tile.damage as u64 + 2;
+        let shooter = self.tile_mut(position);
+        shooter.look_direction = look_direction;
+        shooter.last_shot_slot = now;
+
+        for direction in directions {
+            for target in self.broadside_targets(position, direction, tile.range) {
+                self.npc_attack(target, damage, npc, now, game_actions);
+            }
+        }
+
+        // 记录射击动作
+        let item = GameAction {
+            action_id: self.header.action_id,
+            action_type: GAME_ACTION_SHIP_SHOT,
+            player: npc,
+            target: npc,
+            damage,
+        };
+        self.add_new_game_action(game_actions, item);
+        Ok(true)
+    }
+
+    // NPC攻击玩家的船只,击沉时托管的奖励留在宝箱金库中
+    fn npc_attack(
+        &mut self,
+        position: (usize, usize),
+        damage: u64,
+        npc: Pubkey,
+        now: u64,
+        game_actions: &mut GameActionHistory,
+    ) {
+        let tile = self.tile(position);
+        let attacked_player = self.player_key(&tile);
+        let health = (tile.health as u64).saturating_sub(damage) as u32;
+        let attacked = self.tile_mut(position);
+        attacked.health = health;
+        attacked.last_damage_slot = now;
+        if health == 0 {
+            self.leave_wreck(position, tile, false);
+        }
+
+        // 记录受到伤害动作
+        let item = GameAction {
+            action_id: self.header.action_id,
+            action_type: GAME_ACTION_SHIP_TAKEN_DAMAGE,
+            player: npc,
+            target: attacked_player,
+            damage,
+        };
+        self.add_new_game_action(game_actions, item);
+    }
+
+    // NPC向目标移动一格,优先沿差距较小的方向与目标对齐,以便舷侧对准目标
+    fn npc_step_toward(
+        &mut self,
+        position: (usize, usize),
+        target: (usize, usize),
+        now: u64,
+    ) -> bool {
+        let (dx, dy) = (
+            target.0 as i64 - position.0 as i64,
+            target.1 as i64 - position.1 as i64,
+        );
+        let horizontal = match dx.signum() {
+            1 => Some(1),
+            -1 => Some(3),
+            _ => None,
+        };
+        let vertical = match dy.signum() {
+            1 => Some(2),
+            -1 => Some(0),
+            _ => None,
+        };
+        let order = if dx.abs() <= dy.abs() {
+            [horizontal, vertical]
+        } else {
+            [vertical, horizontal]
+        };
+        order
+            .into_iter()
+            .flatten()
+            .any(|direction| self.npc_move(position, direction, now))
+    }
+
+    // NPC向指定方向移动一格,只能驶入空格子
+    fn npc_move(&mut self, position: (usize, usize), direction: u8, now: u64) -> bool {
+        match self.calculate_new_position(position, direction) {
+            Ok(new_pos) if self.tile(new_pos).state == STATE_EMPTY => {
+                self.tile_mut(position).last_move_slot = now;
+                self.move_ship(position, new_pos, direction);
+                true
+            }
+            _ => false,
+        }
+    }
 
+    /// 开放大逃杀报名
+    ///
+    /// # 返回值
+    /// * `Result<()>` - 开放海域中的区块不能开启大逃杀
+    ///
+    /// # 功能说明
+    /// 清除上一局的风暴,玩家可以出海报名。比赛中调用时取消当前比赛,
+    /// 没有被领取的奖池滚入下一局
+    pub fn open_royale(&mut self) -> Result<()> {
+        if self.header.chunk().is_some() {
+            return Err(SevenSeasError::RoyaleNotAllowed.into());
+        }
+        self.clear_storm();
+        self.header.royale = ROYALE_OPEN;
+        msg!("Battle royale open, pot {}", self.header.royale_pot());
         Ok(())
     }
+
+    /// 开始大逃杀
+    ///
+    /// # 参数
+    /// * `storm_interval_slots` - 风暴两次收缩之间的最少slot数
+    ///
+    /// # 功能说明
+    /// 开始后不能再出海,被击沉或中途返航的船只托管的奖励进入奖池
+    pub fn start_royale(&mut self, storm_interval_slots: u64) -> Result<()> {
+        if self.header.royale != ROYALE_OPEN {
+            return Err(SevenSeasError::RoyaleNotOpen.into());
+        }
+        self.header.royale = ROYALE_RUNNING;
+        self.header.storm_interval_slots = storm_interval_slots;
+        self.header.last_storm_slot = Clock::get()?.slot;
+        msg!(
+            "Battle royale started with {} ships",
+            self.royale_ships().len()
+        );
+        Ok(())
+    }
+
+    /// 风暴收缩一圈,并对风暴中的船只造成伤害
+    ///
+    /// # 参数
+    /// * `game_actions` - 游戏动作历史,记录风暴造成的伤害
+    ///
+    /// # 返回值
+    /// * `Result<u64>` - 受到伤害的船只数量
+    ///
+    /// # 功能说明
+    /// 1. 风暴向内推进一圈,安全区至少保留棋盘中心
+    /// 2. 风暴中的船只(包括NPC)受到 ROYALE_STORM_DAMAGE 点伤害,被击沉时托管的奖励进入奖池
+    /// 3. 风暴中的空格子和宝箱变为风暴,宝箱的奖励进入奖池
+    pub fn storm_tick(&mut self, game_actions: &mut GameActionHistory) -> Result<u64> {
+        if self.header.royale != ROYALE_RUNNING {
+            return Err(SevenSeasError::RoyaleNotRunning.into());
+        }
+        let now = Clock::get()?.slot;
+        if !cooldown_ready(
+            self.header.last_storm_slot,
+            self.header.storm_interval_slots,
+            now,
+        ) {
+            return Err(SevenSeasError::ActionOnCooldown.into());
+        }
+        self.header.last_storm_slot = now;
+
+        // 1. 推进风暴
+        let max_storm = (self.width().min(self.height()).saturating_sub(1) / 2) as u16;
+        if self.header.storm < max_storm {
+            self.header.storm += 1;
+        }
+
+        // 2. 伤害风暴中的船只
+        let mut damaged = 0;
+        for index in 0..MAX_PLAYERS {
+            let slot = self.header.players[index];
+            let position = (slot.x as usize, slot.y as usize);
+            if slot.player == Pubkey::default() || !self.is_in_storm(position) {
+                continue;
+            }
+            let tile = self.tile(position);
+            let health = (tile.health as u64).saturating_sub(ROYALE_STORM_DAMAGE) as u32;
+            let ship = self.tile_mut(position);
+            ship.health = health;
+            ship.last_damage_slot = now;
+            if health == 0 {
+                self.leave_wreck(position, tile, false);
+            }
+            damaged += 1;
+
+            // 记录风暴伤害动作
+            let item = GameAction {
+                action_id: self.header.action_id,
+                action_type: GAME_ACTION_SHIP_STORM_DAMAGE,
+                player: slot.player,
+                target: slot.player,
+                damage: ROYALE_STORM_DAMAGE,
+            };
+            self.add_new_game_action(game_actions, item);
+        }
+
+        // 3. 吞没空格子和宝箱,包括刚被击沉的船只所在的格子
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                if !self.is_in_storm((x, y)) {
+                    continue;
+                }
+                let tile = self.tile((x, y));
+                if tile.state == STATE_CHEST {
+                    self.header.royale_pot += tile.collect_reward;
+                }
+                if tile.state == STATE_EMPTY || tile.state == STATE_CHEST {
+                    self.tile_mut((x, y)).state = STATE_STORM;
+                }
+            }
+        }
+
+        let storm = self.header.storm;
+        msg!(
+            "Storm ring {}, {} ships damaged, pot {}",
+            storm,
+            damaged,
+            self.header.royale_pot()
+        );
+        Ok(damaged)
+    }
+
+    /// 最后一艘玩家的船只领取大逃杀奖池
+    ///
+    /// # 参数
+    /// * `winner` - 最后一艘船的船主
+    /// * `chest_vault` - 宝箱金库,奖池托管在这里
+    ///
+    /// # 返回值
+    /// * `Result<u64>` - 获得的SOL奖励,包括奖池和自己托管的奖励
+    ///
+    /// # 功能说明
+    /// 棋盘上只剩下一艘玩家的船只(不包括NPC)时比赛结束,清除风暴并关闭大逃杀
+    pub fn claim_royale(&mut self, winner: &AccountInfo, chest_vault: &AccountInfo) -> Result<u64> {
+        if self.header.royale != ROYALE_RUNNING {
+            return Err(SevenSeasError::RoyaleNotRunning.into());
+        }
+        let ships = self.royale_ships();
+        let [position] = ships[..] else {
+            return Err(SevenSeasError::RoyaleNotOver.into());
+        };
+        if self.player_key(&self.tile(position)) != *winner.key {
+            return Err(SevenSeasError::Unauthorized.into());
+        }
+
+        let prize = self.header.royale_pot + self.tile(position).collect_reward;
+        self.tile_mut(position).collect_reward = 0;
+        self.header.royale_pot = 0;
+        self.header.royale = ROYALE_OFF;
+        self.clear_storm();
+
+        **chest_vault.try_borrow_mut_lamports()? -= prize;
+        **winner.try_borrow_mut_lamports()? += prize;
+        msg!(
+            "{} won the battle royale and {} lamports",
+            winner.key,
+            prize
+        );
+        Ok(prize)
+    }
+
+    // 棋盘上玩家的船只(不包括NPC)所在的格子
+    fn royale_ships(&self) -> Vec<(usize, usize)> {
+        (0..MAX_PLAYERS)
+            .map(|index| self.header.players[index])
+            .filter(|slot| slot.player != Pubkey::default())
+            .map(|slot| (slot.x as usize, slot.y as usize))
+            .filter(|position| self.tile(*position).npc == NPC_NONE)
+            .collect()
+    }
+
+    // 格子是否在风暴中,即离棋盘边缘的距离小于风暴圈数
+    fn is_in_storm(&self, (x, y): (usize, usize)) -> bool {
+        let edge = x
+            .min(y)
+            .min(self.width() - 1 - x)
+            .min(self.height() - 1 - y);
+        edge < self.header.storm as usize
+    }
+
+    // 清除风暴,风暴格子恢复为空格子
+    fn clear_storm(&mut self) {
+        for x in 0..self.width() {
+            for y in 0..self.height() {
+                if self.tile((x, y)).state == STATE_STORM {
+                    self.tile_mut((x, y)).state = STATE_EMPTY;
+                }
+            }
+        }
+        self.header.storm = 0;
+    }
+}
+
+impl GameActionHistory {
+    /// 初始化
+    pub fn init(&mut self) {
+        self.version = GAME_ACTIONS_VERSION;
+    }
+
+    /// 布局版本
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// 从最初的历史记录迁移,冷却时间默认关闭
+    pub fn from_legacy(legacy: LegacyGameActionHistory) -> Self {
+        Self {
+            version: GAME_ACTIONS_VERSION,
+            id_counter: legacy.id_counter,
+            shoot_cooldown_slots: 0,
+            move_cooldown_slots: 0,
+            game_actions: legacy.game_actions,
+        }
+    }
+
+    /// 设置射击和移动的冷却时间
+    pub fn set_cooldowns(&mut self, shoot_cooldown_slots: u64, move_cooldown_slots: u64) {
+        self.shoot_cooldown_slots = shoot_cooldown_slots;
+        self.move_cooldown_slots = move_cooldown_slots;
+    }
 }
 
 /// 宝箱金库账户结构体
 #[account]
-pub struct ChestVaultAccount {}
+#[derive(InitSpace)]
+pub struct ChestVaultAccount {
+    pub version: u8, // 布局版本
+}
+
+/// 宝箱国库账户结构体
+///
+/// 种子为"treasury",任何人都可以通过 fund_treasury 注资,
+/// spawn_chests 从这里为新宝箱支付奖励,而不是向加入游戏的玩家收取
+#[account]
+#[derive(InitSpace)]
+pub struct TreasuryAccount {
+    pub version: u8, // 布局版本
+}
+
+// 种子为0时使用的初始状态
+const XORSHIFT_ZERO_SEED: u64 = 0x2545_F491_4F6C_DD1D;
 
 /// 简单的伪随机数生成器
 pub struct XorShift64 {
@@ -881,6 +2640,15 @@ pub struct XorShift64 {
 }
 
 impl XorShift64 {
+    /// 使用种子创建随机数生成器
+    ///
+    /// 状态为0时xorshift会一直输出0,种子为0时改用一个非零常量作为初始状态
+    pub fn new(seed: u64) -> Self {
+        Self {
+            a: if seed == 0 { XORSHIFT_ZERO_SEED } else { seed },
+        }
+    }
+
     /// 生成下一个随机数
     pub fn next(&mut self) -> u64 {
         let mut x = self.a;
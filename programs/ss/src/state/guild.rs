@@ -0,0 +1,65 @@
+use crate::errors::SevenSeasError;
+use crate::PROFILE_NAME_LEN;
+use anchor_lang::prelude::*;
+
+/// 公会账户布局版本
+pub const GUILD_VERSION: u8 = 1;
+
+/// 公会最多成员数
+pub const GUILD_MAX_MEMBERS: usize = 16;
+
+/// 公会账户,种子为"guild"和公会编号(小端序u16)
+///
+/// 公会编号同时是团队模式中的队伍编号,0表示不属于任何公会。
+/// 成员的玩家档案记录所属的公会,出海时写入棋盘格子
+#[account]
+#[derive(InitSpace)]
+pub struct Guild {
+    /// 布局版本
+    pub version: u8,
+    /// 公会编号
+    pub id: u16,
+    /// 会长,加入公会需要会长签名
+    pub leader: Pubkey,
+    /// 公会名称,UTF-8编码,不足部分补0
+    pub name: [u8; PROFILE_NAME_LEN],
+    /// 成员列表,包括会长
+    #[max_len(GUILD_MAX_MEMBERS)]
+    pub members: Vec<Pubkey>,
+}
+
+impl Guild {
+    /// 设置公会名称
+    pub fn set_name(&mut self, name: &str) -> Result<()> {
+        let bytes = name.as_bytes();
+        if bytes.len() > PROFILE_NAME_LEN {
+            return Err(SevenSeasError::ProfileNameTooLong.into());
+        }
+        self.name = [0; PROFILE_NAME_LEN];
+        self.name[..bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// 添加成员
+    pub fn add_member(&mut self, member: Pubkey) -> Result<()> {
+        if self.members.len() >= GUILD_MAX_MEMBERS {
+            return Err(SevenSeasError::GuildFull.into());
+        }
+        self.members.push(member);
+        Ok(())
+    }
+
+    /// 移除成员,会长离开时由最早加入的成员接任
+    pub fn remove_member(&mut self, member: &Pubkey) -> Result<()> {
+        let index = self
+            .members
+            .iter()
+            .position(|key| key == member)
+            .ok_or(SevenSeasError::NotAGuildMember)?;
+        self.members.remove(index);
+        if self.leader == *member {
+            self.leader = self.members.first().copied().unwrap_or_default();
+        }
+        Ok(())
+    }
+}
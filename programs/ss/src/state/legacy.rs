@@ -0,0 +1,49 @@
+// 旧账户布局,只在迁移指令中使用
+use crate::GameAction;
+use anchor_lang::prelude::*;
+
+/// 旧船只账户固定分配的大小
+pub const LEGACY_SHIP_SPACE: usize = 1024;
+/// 旧游戏动作历史账户的大小
+pub const LEGACY_GAME_ACTIONS_SPACE: usize = 8 + LegacyGameActionHistory::INIT_SPACE;
+/// 旧宝箱金库账户的大小
+pub const LEGACY_CHEST_VAULT_SPACE: usize = 8;
+/// 最初的游戏数据账户分配的大小,格子和动作ID之后的字节为0
+pub const LEGACY_GAME_DATA_SPACE: usize = 10240;
+/// 最初的游戏数据账户的棋盘边长
+pub const LEGACY_BOARD_SIZE: u16 = 10;
+
+/// 最初的船只
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyShip {
+    pub health: u64,
+    pub kills: u16,
+    pub cannons: u64,
+    pub upgrades: u16,
+    pub xp: u16,
+    pub level: u16,
+    pub start_health: u64,
+}
+
+/// 最初的游戏动作历史
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, InitSpace)]
+pub struct LegacyGameActionHistory {
+    pub id_counter: u64,
+    #[max_len(100)]
+    pub game_actions: Vec<GameAction>,
+}
+
+/// 最初的游戏数据账户中的棋盘格子,账户中是 10x10 个格子之后跟着动作ID
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyTile {
+    pub player: Pubkey,
+    pub state: u8,
+    pub health: u64,
+    pub damage: u64,
+    pub range: u16,
+    pub collect_reward: u64,
+    pub avatar: Pubkey,
+    pub look_direction: u8,
+    pub ship_level: u16,
+    pub start_health: u64,
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+
+/// 掉落表账户布局版本
+pub const LOOT_TABLE_VERSION: u8 = 1;
+
+/// 宝箱等级: 普通
+pub const CHEST_TIER_COMMON: u8 = 0;
+/// 宝箱等级: 稀有
+pub const CHEST_TIER_RARE: u8 = 1;
+/// 宝箱等级: 传说
+pub const CHEST_TIER_LEGENDARY: u8 = 2;
+/// 宝箱等级数量
+pub const CHEST_TIER_COUNT: usize = 3;
+
+/// 每个宝箱等级的掉落条目数量
+pub const LOOT_TABLE_ENTRIES: usize = 4;
+
+/// 掉落物品: 大炮
+pub const LOOT_ITEM_CANNON: u8 = 0;
+/// 掉落物品: 朗姆酒
+pub const LOOT_ITEM_RUM: u8 = 1;
+/// 掉落物品: 链弹
+pub const LOOT_ITEM_CHAIN_SHOT: u8 = 2;
+/// 掉落物品: 霰弹
+pub const LOOT_ITEM_GRAPESHOT: u8 = 3;
+/// 掉落物品: 爆破弹
+pub const LOOT_ITEM_EXPLOSIVE_SHELL: u8 = 4;
+/// 掉落物品: 金币
+pub const LOOT_ITEM_GOLD: u8 = 5;
+/// 掉落物品: 船只皮肤,数量为皮肤编号
+pub const LOOT_ITEM_SKIN: u8 = 6;
+
+/// 船只皮肤数量,玩家档案用位图记录已解锁的皮肤
+pub const MAX_SKINS: u64 = 64;
+
+/// 掉落表中的一个条目
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct LootEntry {
+    /// 权重,为0时不会掉落
+    pub weight: u16,
+    /// 物品,见 LOOT_ITEM_* 常量
+    pub item: u8,
+    /// 数量(整数代币),皮肤为皮肤编号
+    pub amount: u64,
+}
+
+/// 一个宝箱等级的生成权重和奖励
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct ChestTierConfig {
+    /// 生成宝箱时选中这个等级的权重
+    pub spawn_weight: u16,
+    /// 收集时固定获得的金币(整数代币)
+    pub gold: u64,
+    /// 收集时按权重随机掉落其中一个条目,所有权重为0时不掉落物品
+    pub loot: [LootEntry; LOOT_TABLE_ENTRIES],
+}
+
+/// 宝箱掉落表,种子为"loot_table"
+///
+/// 生成宝箱时按权重选择宝箱等级,等级记录在棋盘格子上;
+/// 收集宝箱时按等级发放金币,并用游戏的随机数从掉落条目中抽取一个物品。
+/// 物品先记入玩家档案,再通过 claim_loot 领取
+#[account]
+#[derive(InitSpace)]
+pub struct LootTable {
+    /// 布局版本
+    pub version: u8,
+    /// 按宝箱等级索引的配置
+    pub tiers: [ChestTierConfig; CHEST_TIER_COUNT],
+}
+
+impl LootTable {
+    /// 检查掉落表配置,物品必须已知,皮肤编号必须小于 MAX_SKINS
+    pub fn is_valid(tiers: &[ChestTierConfig; CHEST_TIER_COUNT]) -> bool {
+        tiers.iter().flat_map(|tier| tier.loot.iter()).all(|entry| {
+            entry.weight == 0
+                || match entry.item {
+                    LOOT_ITEM_SKIN => entry.amount < MAX_SKINS,
+                    item => item < LOOT_ITEM_SKIN,
+                }
+        })
+    }
+
+    /// 按生成权重选择宝箱等级,所有权重为0时为普通宝箱
+    pub fn roll_tier(&self, roll: u64) -> u8 {
+        pick_weighted(self.tiers.iter().map(|tier| tier.spawn_weight), roll)
+            .map_or(CHEST_TIER_COMMON, |index| index as u8)
+    }
+
+    /// 宝箱等级的金币奖励
+    pub fn gold(&self, tier: u8) -> u64 {
+        self.tiers.get(tier as usize).map_or(0, |tier| tier.gold)
+    }
+
+    /// 按权重抽取宝箱等级的一个掉落条目
+    pub fn roll_loot(&self, tier: u8, roll: u64) -> Option<LootEntry> {
+        let tier = self.tiers.get(tier as usize)?;
+        pick_weighted(tier.loot.iter().map(|entry| entry.weight), roll)
+            .map(|index| tier.loot[index])
+    }
+}
+
+// 按权重选择一项,所有权重为0时返回None
+fn pick_weighted(weights: impl Iterator<Item = u16> + Clone, roll: u64) -> Option<usize> {
+    let total: u64 = weights.clone().map(u64::from).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = roll % total;
+    for (index, weight) in weights.enumerate() {
+        let weight = u64::from(weight);
+        if roll < weight {
+            return Some(index);
+        }
+        roll -= weight;
+    }
+    None
+}
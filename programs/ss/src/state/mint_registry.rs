@@ -0,0 +1,68 @@
+use crate::{
+    AMMO_CHAIN_SHOT, AMMO_EXPLOSIVE_SHELL, AMMO_GRAPESHOT, LOOT_ITEM_CANNON, LOOT_ITEM_CHAIN_SHOT,
+    LOOT_ITEM_EXPLOSIVE_SHELL, LOOT_ITEM_GRAPESHOT, LOOT_ITEM_RUM,
+};
+use anchor_lang::prelude::*;
+
+/// 铸币注册表账户布局版本
+pub const MINT_REGISTRY_VERSION: u8 = 1;
+
+/// 铸币注册表,种子为"mint_registry"
+///
+/// 固定游戏中使用的金币、大炮、朗姆酒和特殊弹药代币铸币账户,
+/// 所有涉及代币的指令都只接受这里登记的铸币账户
+#[account]
+#[derive(InitSpace)]
+pub struct MintRegistry {
+    /// 布局版本
+    pub version: u8,
+    /// 金币铸币账户,用于奖励和升级费用
+    pub gold_mint: Pubkey,
+    /// 大炮铸币账户,持有数量决定船只的大炮数
+    pub cannon_mint: Pubkey,
+    /// 朗姆酒铸币账户,持有数量决定船只的额外生命值
+    pub rum_mint: Pubkey,
+    /// 链弹铸币账户,为默认值时表示尚未登记
+    pub chain_shot_mint: Pubkey,
+    /// 霰弹铸币账户,为默认值时表示尚未登记
+    pub grapeshot_mint: Pubkey,
+    /// 爆破弹铸币账户,为默认值时表示尚未登记
+    pub explosive_shell_mint: Pubkey,
+}
+
+impl MintRegistry {
+    /// 弹药对应的铸币账户
+    ///
+    /// 普通炮弹不需要代币,未知或尚未登记的弹药返回None
+    pub fn ammo_mint(&self, ammo: u8) -> Option<Pubkey> {
+        let mint = match ammo {
+            AMMO_CHAIN_SHOT => self.chain_shot_mint,
+            AMMO_GRAPESHOT => self.grapeshot_mint,
+            AMMO_EXPLOSIVE_SHELL => self.explosive_shell_mint,
+            _ => return None,
+        };
+        (mint != Pubkey::default()).then_some(mint)
+    }
+
+    /// 代币物品对应的铸币账户,不是代币或尚未登记的物品返回None
+    pub fn loot_mint(&self, item: u8) -> Option<Pubkey> {
+        match item {
+            LOOT_ITEM_CANNON => Some(self.cannon_mint),
+            LOOT_ITEM_RUM => Some(self.rum_mint),
+            LOOT_ITEM_CHAIN_SHOT => self.ammo_mint(AMMO_CHAIN_SHOT),
+            LOOT_ITEM_GRAPESHOT => self.ammo_mint(AMMO_GRAPESHOT),
+            LOOT_ITEM_EXPLOSIVE_SHELL => self.ammo_mint(AMMO_EXPLOSIVE_SHELL),
+            _ => None,
+        }
+    }
+}
+
+/// 把整数代币数量换算为按铸币精度表示的最小单位数量
+pub fn to_base_units(amount: u64, decimals: u8) -> u64 {
+    amount.saturating_mul(10u64.saturating_pow(decimals as u32))
+}
+
+/// 把最小单位数量换算为整数代币数量,不足一个代币的部分舍去
+pub fn from_base_units(amount: u64, decimals: u8) -> u64 {
+    amount / 10u64.saturating_pow(decimals as u32)
+}
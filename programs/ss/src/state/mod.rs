@@ -1,5 +1,27 @@
+pub use ammo::*;
+pub use board_gen::*;
+pub use bounty::*;
+pub use config::*;
 pub use game::*;
+pub use guild::*;
+pub use legacy::*;
+pub use loot::*;
+pub use mint_registry::*;
+pub use profile::*;
 pub use ship::*;
+pub use shop::*;
+pub use tournament::*;
 
+pub mod ammo;
+pub mod board_gen;
+pub mod bounty;
+pub mod config;
 pub mod game;
+pub mod guild;
+pub mod legacy;
+pub mod loot;
+pub mod mint_registry;
+pub mod profile;
 pub mod ship;
+pub mod shop;
+pub mod tournament;
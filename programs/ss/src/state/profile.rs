@@ -0,0 +1,131 @@
+use crate::errors::SevenSeasError;
+use crate::{
+    LOOT_ITEM_CANNON, LOOT_ITEM_CHAIN_SHOT, LOOT_ITEM_EXPLOSIVE_SHELL, LOOT_ITEM_GOLD,
+    LOOT_ITEM_GRAPESHOT, LOOT_ITEM_RUM, LOOT_ITEM_SKIN, MAX_SKINS,
+};
+use anchor_lang::prelude::*;
+
+/// 玩家档案账户布局版本
+pub const PLAYER_PROFILE_VERSION: u8 = 1;
+
+/// 显示名称的最大字节数
+pub const PROFILE_NAME_LEN: usize = 32;
+
+/// 玩家档案,种子为"profile"和玩家公钥
+///
+/// 移动和射击获得的金币先记入档案,再通过claim_rewards一次性领取,
+/// 这样移动和射击指令不需要携带代币相关的账户。
+/// 档案同时记录玩家的终身统计,船只死亡后棋盘格子会被清空,但统计会一直保留
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    /// 布局版本
+    pub version: u8,
+    /// 玩家公钥
+    pub owner: Pubkey,
+    /// 待领取的金币(整数代币)
+    pub pending_gold: u64,
+    /// 显示名称,UTF-8编码,不足部分补0
+    pub name: [u8; PROFILE_NAME_LEN],
+    /// 偏好的头像公钥
+    pub avatar: Pubkey,
+    /// 击沉的船只数量
+    pub kills: u64,
+    /// 被击沉的次数
+    pub deaths: u64,
+    /// 收集的宝箱数量
+    pub chests_collected: u64,
+    /// 累计获得的SOL(lamports)
+    pub sol_earned: u64,
+    /// 累计获得的金币(整数代币),包括尚未领取的部分
+    pub gold_earned: u64,
+    /// 当前选择的船只账户
+    pub selected_ship: Pubkey,
+    /// 从宝箱和残骸中收集的待领取大炮(整数代币)
+    pub pending_cannons: u64,
+    /// 从宝箱中收集的待领取朗姆酒(整数代币)
+    pub pending_rum: u64,
+    /// 从宝箱中收集的待领取链弹(整数代币)
+    pub pending_chain_shot: u64,
+    /// 从宝箱中收集的待领取霰弹(整数代币)
+    pub pending_grapeshot: u64,
+    /// 从宝箱中收集的待领取爆破弹(整数代币)
+    pub pending_explosive_shell: u64,
+    /// 已解锁的船只皮肤位图,第n位对应皮肤编号n
+    pub skins: u64,
+    /// 所属公会编号,0表示不属于任何公会
+    pub guild: u16,
+}
+
+impl PlayerProfile {
+    /// 记入待领取的金币
+    pub fn accrue_gold(&mut self, amount: u64) {
+        self.pending_gold = self.pending_gold.saturating_add(amount);
+        self.gold_earned = self.gold_earned.saturating_add(amount);
+    }
+
+    /// 记入收集到的物品
+    ///
+    /// 金币记入待领取的金币,皮肤直接解锁,代币物品记入待领取数量
+    pub fn accrue_loot(&mut self, item: u8, amount: u64) {
+        match item {
+            LOOT_ITEM_GOLD => self.accrue_gold(amount),
+            LOOT_ITEM_SKIN if amount < MAX_SKINS => self.skins |= 1 << amount,
+            _ => {
+                if let Some(pending) = self.pending_loot(item) {
+                    *pending = pending.saturating_add(amount);
+                }
+            }
+        }
+    }
+
+    /// 取出代币物品的全部待领取数量,不能领取的物品返回None
+    pub fn take_loot(&mut self, item: u8) -> Option<u64> {
+        self.pending_loot(item).map(std::mem::take)
+    }
+
+    // 代币物品的待领取数量
+    fn pending_loot(&mut self, item: u8) -> Option<&mut u64> {
+        match item {
+            LOOT_ITEM_CANNON => Some(&mut self.pending_cannons),
+            LOOT_ITEM_RUM => Some(&mut self.pending_rum),
+            LOOT_ITEM_CHAIN_SHOT => Some(&mut self.pending_chain_shot),
+            LOOT_ITEM_GRAPESHOT => Some(&mut self.pending_grapeshot),
+            LOOT_ITEM_EXPLOSIVE_SHELL => Some(&mut self.pending_explosive_shell),
+            _ => None,
+        }
+    }
+
+    /// 记录一次击杀及获得的SOL奖励
+    pub fn record_kill(&mut self, reward: u64) {
+        self.kills = self.kills.saturating_add(1);
+        self.sol_earned = self.sol_earned.saturating_add(reward);
+    }
+
+    /// 记录一次收集宝箱及获得的SOL奖励
+    pub fn record_chest(&mut self, reward: u64) {
+        self.chests_collected = self.chests_collected.saturating_add(1);
+        self.sol_earned = self.sol_earned.saturating_add(reward);
+    }
+
+    /// 记录一次死亡
+    pub fn record_death(&mut self) {
+        self.deaths = self.deaths.saturating_add(1);
+    }
+
+    /// 设置显示名称
+    pub fn set_name(&mut self, name: &str) -> Result<()> {
+        let bytes = name.as_bytes();
+        if bytes.len() > PROFILE_NAME_LEN {
+            return Err(SevenSeasError::ProfileNameTooLong.into());
+        }
+        self.name = [0; PROFILE_NAME_LEN];
+        self.name[..bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// 解码玩家档案账户数据,供客户端读取档案
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        Self::try_deserialize(&mut &data[..])
+    }
+}
@@ -1,8 +1,17 @@
 use anchor_lang::prelude::*;
 
+/// 船只账户布局版本
+pub const SHIP_VERSION: u8 = 1;
+
+/// 船只被击沉时损失的船载代币比例(百分比)
+pub const SHIP_SINK_LOSS_PERCENT: u64 = 50;
+
 /// 船只结构体,用于存储船只的各项属性
 #[account]
+#[derive(InitSpace)]
 pub struct Ship {
+    /// 布局版本
+    pub version: u8,
     /// 当前生命值
     pub health: u64,
     /// 击杀数量
@@ -17,4 +26,98 @@ pub struct Ship {
     pub level: u16,
     /// 初始生命值
     pub start_health: u64,
+    /// 是否已出海
+    ///
+    /// 出海后没有通过reset_ship返航,说明船只已被击沉,
+    /// 下次出海或打捞时会销毁一部分船载的大炮和朗姆酒
+    pub deployed: bool,
+    /// 船型,决定基础属性,见 SHIP_CLASSES
+    pub class: u8,
+    /// 船只所在的棋盘(游戏数据账户)
+    ///
+    /// 出海和驶入相邻区块时记录,打捞和返航时必须传入这个棋盘
+    pub board: Pubkey,
+    /// 出海或驶入时棋盘的重置次数
+    ///
+    /// 与棋盘当前的重置次数不一致时,船只是被重置整个棋盘移除的,不会结算击沉的损失
+    pub board_epoch: u64,
+}
+
+// 船型,最初的船只迁移后为单桅帆船
+pub const SHIP_CLASS_SLOOP: u8 = 0; // 单桅帆船
+pub const SHIP_CLASS_BRIGANTINE: u8 = 1; // 双桅帆船
+pub const SHIP_CLASS_FRIGATE: u8 = 2; // 护卫舰
+pub const SHIP_CLASS_GALLEON: u8 = 3; // 大帆船
+
+/// 船型的基础属性
+pub struct ShipClassStats {
+    /// 基础生命值
+    pub base_health: u64,
+    /// 最多可装备的大炮数量
+    pub cannon_slots: u64,
+    /// 基础攻击范围
+    pub range: u8,
+    /// 移动速度
+    pub move_speed: u8,
+    /// 最多可升级次数
+    pub max_upgrades: u16,
+}
+
+/// 按船型索引的基础属性
+pub const SHIP_CLASSES: [ShipClassStats; 4] = [
+    // 单桅帆船: 与引入船型之前的船只属性相同
+    ShipClassStats {
+        base_health: 50,
+        cannon_slots: 5,
+        range: 1,
+        move_speed: 3,
+        max_upgrades: 4,
+    },
+    // 双桅帆船
+    ShipClassStats {
+        base_health: 80,
+        cannon_slots: 8,
+        range: 1,
+        move_speed: 2,
+        max_upgrades: 4,
+    },
+    // 护卫舰
+    ShipClassStats {
+        base_health: 120,
+        cannon_slots: 12,
+        range: 2,
+        move_speed: 2,
+        max_upgrades: 3,
+    },
+    // 大帆船
+    ShipClassStats {
+        base_health: 200,
+        cannon_slots: 20,
+        range: 2,
+        move_speed: 1,
+        max_upgrades: 2,
+    },
+];
+
+/// 每次升级后在基础生命值之上增加的生命值,按升级次数索引
+pub const SHIP_UPGRADE_HEALTH_BONUS: [u64; 5] = [0, 50, 100, 250, 450];
+
+/// 升级到3次及以上时增加的攻击范围
+pub const SHIP_UPGRADE_RANGE_BONUS: u8 = 1;
+
+impl Ship {
+    /// 船型的基础属性,未知船型返回None
+    pub fn class_stats(&self) -> Option<&'static ShipClassStats> {
+        SHIP_CLASSES.get(self.class as usize)
+    }
+
+    /// 根据船型和升级次数计算攻击范围
+    pub fn range(&self) -> Option<u8> {
+        let stats = self.class_stats()?;
+        if self.upgrades >= 3 {
+            Some(stats.range + SHIP_UPGRADE_RANGE_BONUS)
+        } else {
+            Some(stats.range)
+        }
+    }
 }
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+/// 商店账户布局版本
+pub const SHOP_VERSION: u8 = 1;
+
+/// 商品: 大炮
+pub const SHOP_ITEM_CANNON: u8 = 0;
+/// 商品: 朗姆酒
+pub const SHOP_ITEM_RUM: u8 = 1;
+
+/// 一天的秒数,用于计算每日限购
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// 商店账户,种子为"shop"
+///
+/// 玩家用金币购买大炮和朗姆酒,价格由管理员设置。
+/// 大炮和朗姆酒的铸币权限需要交给种子为"mint_authority"的PDA
+#[account]
+#[derive(InitSpace)]
+pub struct Shop {
+    /// 布局版本
+    pub version: u8,
+    /// 每门大炮的价格(金币),为0时不出售
+    pub cannon_price: u64,
+    /// 每瓶朗姆酒的价格(金币),为0时不出售
+    pub rum_price: u64,
+    /// 每个钱包每天最多购买的商品数量
+    pub daily_limit: u64,
+}
+
+impl Shop {
+    /// 商品价格,商品不存在或不出售时返回None
+    pub fn price(&self, item: u8) -> Option<u64> {
+        let price = match item {
+            SHOP_ITEM_CANNON => self.cannon_price,
+            SHOP_ITEM_RUM => self.rum_price,
+            _ => return None,
+        };
+        (price > 0).then_some(price)
+    }
+}
+
+/// 钱包的购买记录,种子为"shop_record"和钱包公钥
+#[account]
+#[derive(InitSpace)]
+pub struct ShopPurchaseRecord {
+    /// 布局版本
+    pub version: u8,
+    /// 记录所在的日期(unix时间戳 / SECONDS_PER_DAY)
+    pub day: i64,
+    /// 当天已购买的商品数量
+    pub purchased: u64,
+}
+
+impl ShopPurchaseRecord {
+    /// 记录一次购买,超过每日限购时返回false
+    ///
+    /// # 参数
+    /// * `today` - 当前日期
+    /// * `amount` - 购买数量
+    /// * `daily_limit` - 每日限购数量
+    pub fn record(&mut self, today: i64, amount: u64, daily_limit: u64) -> bool {
+        if self.day != today {
+            self.day = today;
+            self.purchased = 0;
+        }
+        match self.purchased.checked_add(amount) {
+            Some(total) if total <= daily_limit => {
+                self.purchased = total;
+                true
+            }
+            _ => false,
+        }
+    }
+}
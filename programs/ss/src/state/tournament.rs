@@ -0,0 +1,133 @@
+use crate::errors::SevenSeasError;
+use anchor_lang::prelude::*;
+
+/// 锦标赛账户布局版本
+pub const TOURNAMENT_VERSION: u8 = 1;
+
+/// 锦标赛最多参赛人数
+pub const MAX_TOURNAMENT_ENTRIES: usize = 32;
+
+/// 奖金表的名次数量
+pub const TOURNAMENT_PRIZE_PLACES: usize = 4;
+
+/// 奖金表按万分比计算,所有名次之和必须等于这个值
+pub const TOURNAMENT_PAYOUT_TOTAL: u16 = 10_000;
+
+/// 参赛者在锦标赛中的得分
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct TournamentEntry {
+    /// 参赛玩家的钱包
+    pub player: Pubkey,
+    /// 比赛期间击沉的船只和收集的宝箱数量
+    pub score: u64,
+}
+
+/// 限时锦标赛,种子为"tournament"、游戏数据账户和锦标赛编号(小端序u64)
+///
+/// 玩家在结束前支付报名费加入,报名费存入奖池金库。
+/// 比赛期间射击和移动时传入锦标赛账户,击沉船只和收集宝箱各得一分。
+/// 结束后通过 finalize_tournament 按得分排名,按奖金表发放奖池并关闭奖池金库
+#[account]
+#[derive(InitSpace)]
+pub struct Tournament {
+    /// 布局版本
+    pub version: u8,
+    /// 锦标赛编号
+    pub id: u64,
+    /// 比赛所在的游戏数据账户
+    pub game_data_account: Pubkey,
+    /// 创建者(棋盘管理员),结算时奖池金库关闭后的余额退还给创建者
+    pub authority: Pubkey,
+    /// 开始的slot,之前的击杀和宝箱不计分
+    pub start_slot: u64,
+    /// 结束的slot,之后不能再报名和计分
+    pub end_slot: u64,
+    /// 报名费(lamports)
+    pub entry_fee: u64,
+    /// 奖池中的SOL(lamports)
+    pub prize_pool: u64,
+    /// 按名次的奖金比例(万分比)
+    pub payouts: [u16; TOURNAMENT_PRIZE_PLACES],
+    /// 是否已经发放奖金
+    pub finalized: bool,
+    /// 参赛者,按报名顺序排列
+    #[max_len(MAX_TOURNAMENT_ENTRIES)]
+    pub entries: Vec<TournamentEntry>,
+}
+
+impl Tournament {
+    /// 检查奖金表,比例之和必须等于 TOURNAMENT_PAYOUT_TOTAL,且不能跳过名次
+    pub fn is_valid_payouts(payouts: &[u16; TOURNAMENT_PRIZE_PLACES]) -> bool {
+        let total: u32 = payouts.iter().map(|share| *share as u32).sum();
+        total == TOURNAMENT_PAYOUT_TOTAL as u32
+            && payouts.windows(2).all(|pair| pair[0] > 0 || pair[1] == 0)
+    }
+
+    /// 指定slot是否在比赛期间
+    pub fn is_running(&self, now: u64) -> bool {
+        (self.start_slot..self.end_slot).contains(&now)
+    }
+
+    /// 添加参赛者
+    pub fn add_entry(&mut self, player: Pubkey) -> Result<()> {
+        if self.entries.iter().any(|entry| entry.player == player) {
+            return Err(SevenSeasError::AlreadyInTournament.into());
+        }
+        if self.entries.len() >= MAX_TOURNAMENT_ENTRIES {
+            return Err(SevenSeasError::TournamentFull.into());
+        }
+        self.entries.push(TournamentEntry { player, score: 0 });
+        Ok(())
+    }
+
+    /// 比赛期间为参赛者加分,不在比赛期间或不是参赛者时忽略
+    pub fn record_score(&mut self, player: &Pubkey, points: u64, now: u64) {
+        if points == 0 || !self.is_running(now) {
+            return;
+        }
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.player == *player)
+        {
+            entry.score = entry.score.saturating_add(points);
+        }
+    }
+
+    /// 按得分从高到低排名,得分相同时先报名的排在前面
+    pub fn ranking(&self) -> Vec<TournamentEntry> {
+        let mut ranking = self.entries.clone();
+        ranking.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        ranking
+    }
+
+    /// 按名次计算奖金
+    ///
+    /// 只有得分大于0的参赛者可以获奖。获奖人数少于名次数量时,
+    /// 没有人获得的名次的比例按比例分给其余名次,按比例分配剩下的零头归第一名
+    pub fn prizes(&self) -> Vec<u64> {
+        let scored = self.entries.iter().filter(|entry| entry.score > 0).count();
+        let places = scored.min(TOURNAMENT_PRIZE_PLACES);
+        let shares = &self.payouts[..places];
+        let total: u64 = shares.iter().map(|share| *share as u64).sum();
+        if total == 0 {
+            return Vec::new();
+        }
+        let mut prizes: Vec<u64> = shares
+            .iter()
+            .map(|share| (self.prize_pool as u128 * *share as u128 / total as u128) as u64)
+            .collect();
+        prizes[0] += self.prize_pool - prizes.iter().sum::<u64>();
+        prizes
+    }
+}
+
+/// 锦标赛的奖池金库,种子为"prize_vault"和锦标赛账户
+///
+/// 报名费存入这里,finalize_tournament 从这里向获奖者发放奖金
+#[account]
+#[derive(InitSpace)]
+pub struct PrizeVault {
+    /// 布局版本
+    pub version: u8,
+}
@@ -10,9 +10,13 @@ import {
   mintTo,
   getAccount,
   getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  setAuthority,
+  AuthorityType,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import fs from "fs";
+import { assert } from "chai";
 import { Keypair } from "@solana/web3.js";
 import {
   SystemProgram,
@@ -67,6 +71,49 @@ describe("ss", () => {
 
   // 所有者密钥
 
+  // 游戏配置账户PDA
+  const [gameConfig] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+
+  // 玩家档案PDA
+  const [playerProfile] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("profile"), player.publicKey.toBuffer()],
+    program.programId
+  );
+
+  // 铸币注册表PDA
+  const [mintRegistry] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("mint_registry")],
+    program.programId
+  );
+
+  // 宝箱掉落表PDA
+  const [tournament] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("tournament"),
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("level")],
+        program.programId
+      )[0].toBuffer(),
+      new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+    ],
+    program.programId
+  );
+  const [prizeVault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("prize_vault"), tournament.toBuffer()],
+    program.programId
+  );
+  const [lootTable] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("loot_table")],
+    program.programId
+  );
+  const [gameActions] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("gameActions_history")],
+    program.programId
+  );
+
   console.log("player 地址是: ", player.publicKey.toBase58());
 
   it("初始化!", async () => {
@@ -245,12 +292,47 @@ describe("ss", () => {
       .rpc();
     console.log("initialize game actions 交易签名", initGameActionsTx);
 
+    // 初始化游戏配置账户
+    const initConfigTx = await program.methods
+      .initializeConfig()
+      .accounts({
+        signer: player.publicKey,
+        config: gameConfig,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("initialize config 交易签名", initConfigTx);
+
+    // 登记金币、大炮和朗姆酒铸币账户
+    const initMintRegistryTx = await program.methods
+      .initializeMintRegistry()
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+        mintRegistry: mintRegistry,
+        goldMint: gold_mint,
+        cannonMint: cannon_mint,
+        rumMint: rum_mint,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("initialize mint registry 交易签名", initMintRegistryTx);
+
     // 初始化游戏数据账户
     const initGameDataTx = await program.methods
-      .initializeGameData()
+      .initializeGameData({
+        seed: new anchor.BN(42),
+        islandDensity: 10,
+        reefDensity: 10,
+        chestCount: 2,
+        portCount: 1,
+      })
       .accounts({
         signer: player.publicKey,
         newGameDataAccount: level,
+        chestVault: chestVault,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([player])
@@ -269,6 +351,231 @@ describe("ss", () => {
     console.log("铸币签名: " + mintToProgramResult);
   });
 
+  it("扩容棋盘!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+
+    // 管理员把棋盘从10x10扩容到16x16
+    const tx = await program.methods
+      .resizeGameData(16, 16)
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("扩容棋盘交易签名", tx);
+  });
+
+  it("迁移最初的棋盘!", async () => {
+    // 测试验证器预先加载了一个最初布局的10x10游戏数据账户(10240字节),
+    // 格子(2,3)中有一艘船,格子(5,5)中有一个宝箱
+    const legacyLevel = new PublicKey(
+      "D7iFjUT4s6M9Lv385m6Hikqi2fUdtxu7Am2Cj8DCMqWK"
+    );
+    const shipOwner = new PublicKey(
+      "2fHDGan8qkTQ9H1TBLsiopTrGsbJQSbaabhVonRKpCpo"
+    );
+
+    const tx = await program.methods
+      .migrateGameData()
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: legacyLevel,
+        config: gameConfig,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("迁移最初的棋盘交易签名", tx);
+
+    // 迁移后由配置账户的管理员管理,船只登记在玩家表中
+    const level = await program.account.gameDataAccount.fetch(legacyLevel);
+    assert.equal(level.version, 1);
+    assert.ok(level.authority.equals(player.publicKey));
+    assert.equal(level.width, 10);
+    assert.equal(level.height, 10);
+    assert.equal(level.actionId.toNumber(), 42);
+    assert.ok(level.players[0].player.equals(shipOwner));
+    assert.equal(level.players[0].x, 2);
+    assert.equal(level.players[0].y, 3);
+  });
+
+  it("残骸!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+
+    // 被击沉的船只把一半托管奖励留在残骸中
+    const tx = await program.methods
+      .setWreckPercent(50)
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+      })
+      .signers([player])
+      .rpc();
+    console.log("设置残骸比例交易签名", tx);
+  });
+
+  it("商店!", async () => {
+    const [shop] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("shop")],
+      program.programId
+    );
+    const [purchaseRecord] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("shop_record"), player.publicKey.toBuffer()],
+      program.programId
+    );
+    const [mintAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_authority")],
+      program.programId
+    );
+    const [goldVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_vault"), goldTokenMint.toBuffer()],
+      program.programId
+    );
+
+    // 开设商店: 大炮10金币, 朗姆酒5金币, 每天限购20件
+    let tx = await program.methods
+      .initializeShop(new anchor.BN(10), new anchor.BN(5), new anchor.BN(20))
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+        shop: shop,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("开设商店交易签名", tx);
+
+    // 把大炮的铸币权限交给程序
+    await setAuthority(
+      anchor.getProvider().connection,
+      tokenOwnerKeypair,
+      cannonTokenMint,
+      tokenOwnerKeypair,
+      AuthorityType.MintTokens,
+      mintAuthority
+    );
+
+    const playerGoldTokenAccount = await getOrCreateAssociatedTokenAccount(
+      anchor.getProvider().connection,
+      player,
+      goldTokenMint,
+      player.publicKey
+    );
+    const playerCannonTokenAccount = await getOrCreateAssociatedTokenAccount(
+      anchor.getProvider().connection,
+      player,
+      cannonTokenMint,
+      player.publicKey
+    );
+
+    // 购买2门大炮
+    tx = await program.methods
+      .buy(0, new anchor.BN(2))
+      .accounts({
+        config: gameConfig,
+        signer: player.publicKey,
+        shop: shop,
+        purchaseRecord: purchaseRecord,
+        mintRegistry: mintRegistry,
+        playerGoldAccount: playerGoldTokenAccount.address,
+        vaultTokenAccount: goldVault,
+        goldMint: goldTokenMint,
+        playerItemAccount: playerCannonTokenAccount.address,
+        itemMint: cannonTokenMint,
+        mintAuthority: mintAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("购买大炮交易签名", tx);
+  });
+
+  it("暂停!", async () => {
+    // 管理员暂停射击后再恢复
+    let tx = await program.methods
+      .setPaused(0b100)
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+      })
+      .signers([player])
+      .rpc();
+    console.log("暂停射击交易签名", tx);
+
+    tx = await program.methods
+      .setPaused(0)
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+      })
+      .signers([player])
+      .rpc();
+    console.log("恢复游戏交易签名", tx);
+  });
+
+  it("冷却时间!", async () => {
+    const [gameActions] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gameActions_history")],
+      program.programId
+    );
+
+    // 测试中不限制射击和移动频率
+    const tx = await program.methods
+      .setCooldowns(new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+        gameActions: gameActions,
+      })
+      .signers([player])
+      .rpc();
+    console.log("设置冷却时间交易签名", tx);
+  });
+
+  it("掉落表!", async () => {
+    const entry = (weight: number, item: number, amount: number) => ({
+      weight,
+      item,
+      amount: new anchor.BN(amount),
+    });
+    const none = entry(0, 0, 0);
+
+    // 普通宝箱只给金币,稀有宝箱掉落大炮或朗姆酒,传说宝箱掉落爆破弹或皮肤
+    const tx = await program.methods
+      .setLootTable([
+        { spawnWeight: 80, gold: new anchor.BN(10), loot: [none, none, none, none] },
+        {
+          spawnWeight: 15,
+          gold: new anchor.BN(25),
+          loot: [entry(1, 0, 1), entry(1, 1, 5), none, none],
+        },
+        {
+          spawnWeight: 5,
+          gold: new anchor.BN(100),
+          loot: [entry(3, 4, 2), entry(1, 6, 1), none, none],
+        },
+      ])
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+        lootTable: lootTable,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("设置掉落表交易签名", tx);
+  });
+
   it("初始化船只!", async () => {
     let confirmOptions = {
       skipPreflight: true,
@@ -334,7 +641,7 @@ describe("ss", () => {
 
     // 初始化船只
     let tx = await program.methods
-      .initializeShip()
+      .initializeShip(0)
       .accounts({
         newShip: shipPDA,
         signer: player.publicKey,
@@ -349,6 +656,8 @@ describe("ss", () => {
     tx = await program.methods
       .upgradeShip()
       .accounts({
+        config: gameConfig,
+        mintRegistry: mintRegistry,
         newShip: shipPDA,
         signer: player.publicKey,
         nftAccount: player.publicKey,
@@ -365,6 +674,8 @@ describe("ss", () => {
     tx = await program.methods
       .upgradeShip()
       .accounts({
+        config: gameConfig,
+        mintRegistry: mintRegistry,
         newShip: shipPDA,
         signer: player.publicKey,
         nftAccount: player.publicKey,
@@ -418,21 +729,82 @@ describe("ss", () => {
     );
     console.log("玩家朗姆酒账户: " + playerRumTokenAccount.address.toString());
 
+    // 船载金库PDA
+    const [shipCannonVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ship_cannons"), shipPDA.toBuffer()],
+      program.programId
+    );
+    const [shipRumVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ship_rum"), shipPDA.toBuffer()],
+      program.programId
+    );
+    const [tokenAccountOwnerPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_account_owner_pda", "utf8")],
+      program.programId
+    );
+
+    // 把大炮装备到船上
+    let equipTx = await program.methods
+      .equipCannons(new anchor.BN(5))
+      .accounts({
+        config: gameConfig,
+        signer: player.publicKey,
+        ship: shipPDA,
+        nftAccount: player.publicKey,
+        playerCannonAccount: playerCannonTokenAccount.address,
+        shipCannonVault: shipCannonVault,
+        cannonMint: cannonTokenMint,
+        mintRegistry: mintRegistry,
+        tokenAccountOwnerPda: tokenAccountOwnerPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("装备大炮交易签名", equipTx);
+
+    // 往船上补充朗姆酒
+    equipTx = await program.methods
+      .stockRum(new anchor.BN(20))
+      .accounts({
+        config: gameConfig,
+        signer: player.publicKey,
+        ship: shipPDA,
+        nftAccount: player.publicKey,
+        playerRumAccount: playerRumTokenAccount.address,
+        shipRumVault: shipRumVault,
+        rumMint: rumTokenMint,
+        mintRegistry: mintRegistry,
+        tokenAccountOwnerPda: tokenAccountOwnerPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("补充朗姆酒交易签名", equipTx);
+
     // 生成玩家
     const tx = await program.methods
       .spawnPlayer(avatarPubkey.publicKey)
       .accounts({
+        config: gameConfig,
+        mintRegistry: mintRegistry,
+        lootTable: lootTable,
         player: player.publicKey,
         tokenAccountOwner: player.publicKey,
         gameDataAccount: level,
         chestVault: chestVault,
         nftAccount: player.publicKey,
         ship: shipPDA,
+        profile: playerProfile,
         systemProgram: anchor.web3.SystemProgram.programId,
-        cannonTokenAccount: playerCannonTokenAccount.address,
+        shipCannonVault: shipCannonVault,
         cannonMint: cannonTokenMint,
-        rumTokenAccount: playerRumTokenAccount.address,
+        shipRumVault: shipRumVault,
         rumMint: rumTokenMint,
+        tokenAccountOwnerPda: tokenAccountOwnerPda,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       })
@@ -441,6 +813,47 @@ describe("ss", () => {
     console.log("交易签名", result);
   });
 
+  it("锦标赛!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const slot = await provider.connection.getSlot();
+
+    // 比赛持续30个slot,冠军获得全部奖池
+    let tx = await program.methods
+      .createTournament(
+        new anchor.BN(1),
+        new anchor.BN(slot),
+        new anchor.BN(slot + 30),
+        new anchor.BN(0.01 * LAMPORTS_PER_SOL),
+        [10000, 0, 0, 0]
+      )
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+        tournament: tournament,
+        prizeVault: prizeVault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("创建锦标赛交易签名", tx);
+
+    tx = await program.methods
+      .joinTournament()
+      .accounts({
+        config: gameConfig,
+        player: player.publicKey,
+        tournament: tournament,
+        prizeVault: prizeVault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("报名锦标赛交易签名", tx);
+  });
+
   it("移动!", async () => {
     let confirmOptions = {
       skipPreflight: true,
@@ -488,24 +901,95 @@ describe("ss", () => {
     const tx = await program.methods
       .movePlayerV2(2)
       .accounts({
+        config: gameConfig,
         player: player.publicKey,
+        profile: playerProfile,
+        targetProfile: null,
+        ship: null,
         gameDataAccount: level,
+        neighbourGameDataAccount: null,
         chestVault: chestVault,
-        tokenAccountOwner: player.publicKey,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        tokenAccountOwnerPda: tokenAccountOwnerPda,
-        vaultTokenAccount: token_vault,
-        playerTokenAccount: playerTokenAccount.address,
-        mintOfTokenBeingSent: goldTokenMint,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         gameActions: gameActions,
+        lootTable: lootTable,
+        tournament: tournament,
       })
       .signers([player])
       .rpc();
     console.log("交易签名", tx);
   });
 
+  it("修理船只!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const [gameActions] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gameActions_history")],
+      program.programId
+    );
+    const playerRumTokenAccount = await getOrCreateAssociatedTokenAccount(
+      anchor.getProvider().connection,
+      player,
+      rumTokenMint,
+      player.publicKey
+    );
+
+    // 船只满血或刚受过伤时不能修理
+    try {
+      const tx = await program.methods
+        .repair(new anchor.BN(5))
+        .accounts({
+          player: player.publicKey,
+          gameDataAccount: level,
+          gameActions: gameActions,
+          playerRumAccount: playerRumTokenAccount.address,
+          rumMint: rumTokenMint,
+          mintRegistry: mintRegistry,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          config: gameConfig,
+        })
+        .signers([player])
+        .rpc();
+      console.log("修理交易签名", tx);
+    } catch (e) {
+      const error = String(e);
+      if (
+        !error.includes("NothingToRepair") &&
+        !error.includes("RepairNotAllowed")
+      ) {
+        throw e;
+      }
+    }
+  });
+
+  it("轻量移动!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const [gameActions] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gameActions_history")],
+      program.programId
+    );
+
+    // 目标格子是宝箱或其他船只时需要改用完整的移动指令
+    try {
+      const tx = await program.methods
+        .moveLite(0)
+        .accounts({
+          config: gameConfig,
+          player: player.publicKey,
+          gameDataAccount: level,
+          gameActions: gameActions,
+        })
+        .signers([player])
+        .rpc();
+      console.log("交易签名", tx);
+    } catch (e) {
+      if (!String(e).includes("DestinationRequiresFullMove")) throw e;
+    }
+  });
+
   it("射击!", async () => {
     let confirmOptions = {
       skipPreflight: process.env.NODE_ENV === "test", // 在测试中使用 confirmed 提供更好的可靠性
@@ -550,30 +1034,97 @@ describe("ss", () => {
 
     // 射击
     const tx = await program.methods
-      .shoot(0)
+      .shoot(0) // 普通炮弹,不需要弹药代币账户
       .accounts({
+        config: gameConfig,
         player: player.publicKey,
-        tokenAccountOwner: player.publicKey,
+        profile: playerProfile,
         gameDataAccount: level,
+        neighbourGameDataAccount: null,
         chestVault: chestVault,
         gameActions: gameActions,
-        tokenAccountOwnerPda: tokenAccountOwnerPda,
-        vaultTokenAccount: token_vault,
-        playerTokenAccount: playerTokenAccount.address,
-        mintOfTokenBeingSent: goldTokenMint,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        mintRegistry: mintRegistry,
+        ammoMint: null,
+        playerAmmoAccount: null,
+        tokenProgram: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tournament: tournament,
       })
       .signers([player])
       .rpc();
     console.log("交易签名", tx);
   });
 
-  it("克苏鲁!", async () => {
-    await transfer(player.publicKey, 0.1 * LAMPORTS_PER_SOL);
+  it("暂停后不能射击!", async () => {
+    await setPaused(0b100);
+    try {
+      await shootOnce();
+      assert.fail("暂停射击后射击应该失败");
+    } catch (e) {
+      assert.include(String(e), "GamePaused");
+    } finally {
+      await setPaused(0);
+    }
+  });
 
-    let confirmOptions = {
-      skipPreflight: true,
+  it("冷却中不能射击!", async () => {
+    await setCooldowns(1000, 0);
+    try {
+      // 上一次射击可能已经在冷却中,第二次射击一定在冷却中
+      await shootOnce().catch((e) => {
+        if (!String(e).includes("ActionOnCooldown")) throw e;
+      });
+      await shootOnce();
+      assert.fail("冷却中射击应该失败");
+    } catch (e) {
+      assert.include(String(e), "ActionOnCooldown");
+    } finally {
+      await setCooldowns(0, 0);
+    }
+  });
+
+  it("结算锦标赛!", async () => {
+    // 等待比赛结束
+    const { endSlot, entries } = await program.account.tournament.fetch(
+      tournament
+    );
+    while ((await provider.connection.getSlot()) < endSlot.toNumber()) {
+      await new Promise((resolve) => setTimeout(resolve, 400));
+    }
+
+    // 按排名传入得分大于0的参赛者,得分相同时先报名的排在前面
+    const winners = entries
+      .filter((entry) => entry.score.gtn(0))
+      .sort((a, b) => b.score.cmp(a.score))
+      .map((entry) => ({
+        pubkey: entry.player,
+        isWritable: true,
+        isSigner: false,
+      }));
+    const tx = await program.methods
+      .finalizeTournament()
+      .accounts({
+        signer: player.publicKey,
+        tournament: tournament,
+        prizeVault: prizeVault,
+        authority: player.publicKey,
+      })
+      .remainingAccounts(winners)
+      .signers([player])
+      .rpc();
+    console.log("结算锦标赛交易签名", tx);
+
+    const result = await program.account.tournament.fetch(tournament);
+    console.log("冠军得分: " + result.entries[0].score.toString());
+    // 奖池金库在结算时关闭
+    assert.isNull(await provider.connection.getAccountInfo(prizeVault));
+  });
+
+  it("克苏鲁!", async () => {
+    await transfer(player.publicKey, 0.1 * LAMPORTS_PER_SOL);
+
+    let confirmOptions = {
+      skipPreflight: true,
     };
 
     // 查找游戏相关PDA
@@ -616,23 +1167,734 @@ describe("ss", () => {
     const tx = await program.methods
       .cthulhu(0)
       .accounts({
+        config: gameConfig,
         player: player.publicKey,
-        tokenAccountOwner: player.publicKey,
         gameDataAccount: level,
         chestVault: chestVault,
         gameActions: gameActions,
-        tokenAccountOwnerPda: tokenAccountOwnerPda,
-        vaultTokenAccount: token_vault,
+      })
+      .signers([player])
+      .rpc();
+    console.log("交易签名", tx);
+  });
+
+  it("补充宝箱!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    const [treasury] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury")],
+      program.programId
+    );
+
+    // 国库为宝箱奖励注资
+    let tx = await program.methods
+      .fundTreasury(new anchor.BN(0.2 * LAMPORTS_PER_SOL))
+      .accounts({
+        funder: player.publicKey,
+        treasury: treasury,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("国库注资交易签名", tx);
+
+    // 每1000个水域格子保持20个宝箱,最多3个
+    tx = await program.methods
+      .setChestSpawning(20, 3)
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+      })
+      .signers([player])
+      .rpc();
+    console.log("设置宝箱密度交易签名", tx);
+
+    tx = await program.methods
+      .spawnChests()
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+        chestVault: chestVault,
+        treasury: treasury,
+        lootTable: lootTable,
+        config: gameConfig,
+      })
+      .signers([player])
+      .rpc();
+    console.log("补充宝箱交易签名", tx);
+  });
+
+  it("NPC海盗船!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    const [treasury] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury")],
+      program.programId
+    );
+    const [gameActions] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gameActions_history")],
+      program.programId
+    );
+
+    // 国库为NPC的击沉赏金注资
+    let tx = await program.methods
+      .fundTreasury(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+      .accounts({
+        funder: player.publicKey,
+        treasury: treasury,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("国库注资交易签名", tx);
+
+    // 生成一艘追击玩家的单桅帆船
+    tx = await program.methods
+      .spawnNpc(2, 0)
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+        chestVault: chestVault,
+        treasury: treasury,
+      })
+      .signers([player])
+      .rpc();
+    console.log("生成NPC交易签名", tx);
+
+    tx = await program.methods
+      .npcTick()
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+        gameActions: gameActions,
+        config: gameConfig,
+      })
+      .signers([player])
+      .rpc();
+    console.log("NPC行动交易签名", tx);
+  });
+
+  it("领取奖励!", async () => {
+    const [goldVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_vault"), goldTokenMint.toBuffer()],
+      program.programId
+    );
+    const [tokenAccountOwnerPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_account_owner_pda", "utf8")],
+      program.programId
+    );
+    const playerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      anchor.getProvider().connection,
+      player,
+      goldTokenMint,
+      player.publicKey
+    );
+
+    const profile = await program.account.playerProfile.fetch(playerProfile);
+    console.log("待领取金币: " + profile.pendingGold.toString());
+    console.log("待领取大炮: " + profile.pendingCannons.toString());
+
+    // 领取从残骸中收集的大炮
+    if (!profile.pendingCannons.isZero()) {
+      const [mintAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("mint_authority")],
+        program.programId
+      );
+      const playerCannonAccount = await getAssociatedTokenAddress(
+        cannonTokenMint,
+        player.publicKey
+      );
+      const tx = await program.methods
+        .claimLoot(0)
+        .accounts({
+          config: gameConfig,
+          player: player.publicKey,
+          profile: playerProfile,
+          mintRegistry: mintRegistry,
+          playerItemAccount: playerCannonAccount,
+          itemMint: cannonTokenMint,
+          mintAuthority: mintAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc();
+      console.log("领取大炮交易签名", tx);
+    }
+
+    if (profile.pendingGold.isZero()) {
+      return;
+    }
+
+    // 领取移动和射击获得的金币
+    const tx = await program.methods
+      .claimRewards()
+      .accounts({
+        config: gameConfig,
+        player: player.publicKey,
+        profile: playerProfile,
         playerTokenAccount: playerTokenAccount.address,
-        mintOfTokenBeingSent: goldTokenMint,
+        vaultTokenAccount: goldVault,
+        tokenAccountOwnerPda: tokenAccountOwnerPda,
+        goldMint: goldTokenMint,
+        mintRegistry: mintRegistry,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([player])
       .rpc();
-    console.log("交易签名", tx);
+    console.log("领取奖励交易签名", tx);
+  });
+
+  it("玩家档案!", async () => {
+    const tx = await program.methods
+      .updateProfile("Captain", anchor.web3.Keypair.generate().publicKey)
+      .accounts({
+        player: player.publicKey,
+        profile: playerProfile,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("设置档案交易签名", tx);
+
+    const profile = await program.account.playerProfile.fetch(playerProfile);
+    console.log(
+      "击杀: " +
+        profile.kills.toString() +
+        " 死亡: " +
+        profile.deaths.toString() +
+        " 宝箱: " +
+        profile.chestsCollected.toString()
+    );
+  });
+
+  it("悬赏!", async () => {
+    const target = anchor.web3.Keypair.generate().publicKey;
+    const [bounty] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("bounty"), target.toBuffer(), player.publicKey.toBuffer()],
+      program.programId
+    );
+    const [goldVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_vault"), goldTokenMint.toBuffer()],
+      program.programId
+    );
+    const [tokenAccountOwnerPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_account_owner_pda")],
+      program.programId
+    );
+    const playerGoldAccount = await getAssociatedTokenAddress(
+      goldTokenMint,
+      player.publicKey
+    );
+
+    // 悬赏0.01 SOL,有效期1个slot
+    let tx = await program.methods
+      .placeBounty(
+        new anchor.BN(0.01 * LAMPORTS_PER_SOL),
+        new anchor.BN(0),
+        new anchor.BN(1)
+      )
+      .accounts({
+        config: gameConfig,
+        placer: player.publicKey,
+        target: target,
+        bounty: bounty,
+        mintRegistry: mintRegistry,
+        placerGoldAccount: playerGoldAccount,
+        vaultTokenAccount: goldVault,
+        goldMint: goldTokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("悬赏交易签名", tx);
+
+    // 按目标筛选悬赏账户,客户端据此标记被通缉的船只
+    const wanted = await program.account.bounty.all([
+      { memcmp: { offset: 8 + 1 + 32, bytes: target.toBase58() } },
+    ]);
+    console.log("悬赏数量: " + wanted.length);
+
+    // 等待悬赏过期后退款
+    const { expirySlot } = await program.account.bounty.fetch(bounty);
+    while ((await provider.connection.getSlot()) <= expirySlot.toNumber()) {
+      await new Promise((resolve) => setTimeout(resolve, 400));
+    }
+    tx = await program.methods
+      .refundBounty()
+      .accounts({
+        config: gameConfig,
+        placer: player.publicKey,
+        bounty: bounty,
+        placerGoldAccount: playerGoldAccount,
+        vaultTokenAccount: goldVault,
+        tokenAccountOwnerPda: tokenAccountOwnerPda,
+        goldMint: goldTokenMint,
+        mintRegistry: mintRegistry,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("退还悬赏交易签名", tx);
+  });
+
+  it("公会!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const guildId = 7;
+    const guildIdBytes = Buffer.alloc(2);
+    guildIdBytes.writeUInt16LE(guildId);
+    const [guild] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("guild"), guildIdBytes],
+      program.programId
+    );
+
+    let tx = await program.methods
+      .createGuild(guildId, "Black Flag")
+      .accounts({
+        leader: player.publicKey,
+        guild: guild,
+        profile: playerProfile,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("创建公会交易签名", tx);
+
+    // 开启团队模式,之后出海的船只以公会编号作为队伍
+    tx = await program.methods
+      .setTeamMode(true)
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+      })
+      .signers([player])
+      .rpc();
+    console.log("开启团队模式交易签名", tx);
+
+    const profile = await program.account.playerProfile.fetch(playerProfile);
+    console.log("所属公会: " + profile.guild);
+
+    tx = await program.methods
+      .leaveGuild()
+      .accounts({
+        member: player.publicKey,
+        guild: guild,
+        profile: playerProfile,
+      })
+      .signers([player])
+      .rpc();
+    console.log("离开公会交易签名", tx);
+
+    tx = await program.methods
+      .setTeamMode(false)
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+      })
+      .signers([player])
+      .rpc();
+    console.log("关闭团队模式交易签名", tx);
   });
 
+  it("大逃杀!", async () => {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    const [gameActions] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("gameActions_history")],
+      program.programId
+    );
+
+    let tx = await program.methods
+      .openRoyale()
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+      })
+      .signers([player])
+      .rpc();
+    console.log("开放大逃杀交易签名", tx);
+
+    // 风暴收缩没有间隔,便于测试
+    tx = await program.methods
+      .startRoyale(new anchor.BN(0))
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+      })
+      .signers([player])
+      .rpc();
+    console.log("开始大逃杀交易签名", tx);
+
+    tx = await program.methods
+      .stormTick()
+      .accounts({
+        signer: player.publicKey,
+        gameDataAccount: level,
+        gameActions: gameActions,
+        config: gameConfig,
+      })
+      .signers([player])
+      .rpc();
+    console.log("风暴收缩交易签名", tx);
+
+    // 棋盘上只剩下玩家的船只时领取奖池
+    try {
+      tx = await program.methods
+        .claimRoyale()
+        .accounts({
+          config: gameConfig,
+          player: player.publicKey,
+          gameDataAccount: level,
+          chestVault: chestVault,
+        })
+        .signers([player])
+        .rpc();
+      console.log("领取大逃杀奖池交易签名", tx);
+    } catch (e) {
+      if (!String(e).includes("RoyaleNotOver")) throw e;
+    }
+  });
+
+  it("开放海域!", async () => {
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+
+    // 两个相邻的空区块
+    const west = await initializeChunk(0, 0);
+    const east = await initializeChunk(1, 0);
+
+    // 水手A驶到西区块的上边缘,再向右驶入东区块
+    const a = await spawnSailor(west);
+    await sailToEdge(a, west, 0);
+    try {
+      await sail(a, west, 1, east, { ship: null });
+      assert.fail("传入相邻区块时必须传入船只账户");
+    } catch (e) {
+      assert.include(String(e), "MissingShipAccount");
+    }
+    for (let i = 0; i < 10; i++) {
+      await sail(a, west, 1, east);
+      const ship = await program.account.ship.fetch(shipOf(a.publicKey));
+      if (ship.board.equals(east)) break;
+    }
+    const ship = await program.account.ship.fetch(shipOf(a.publicKey));
+    assert.ok(ship.board.equals(east), "水手A应该驶入了东区块");
+
+    // 水手B停在西区块的右上角,朝向为上时向左右两侧开炮,炮弹越过区块边缘命中水手A
+    const b = await spawnSailor(west);
+    await sailToEdge(b, west, 1);
+    await sailToEdge(b, west, 0);
+    await sail(b, west, 2);
+    await sail(b, west, 0);
+    const tx = await program.methods
+      .shoot(0)
+      .accounts({
+        config: gameConfig,
+        player: b.publicKey,
+        profile: profileOf(b.publicKey),
+        gameDataAccount: west,
+        neighbourGameDataAccount: east,
+        chestVault: chestVault,
+        gameActions: gameActions,
+        mintRegistry: mintRegistry,
+        ammoMint: null,
+        playerAmmoAccount: null,
+        tokenProgram: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tournament: null,
+      })
+      .signers([b])
+      .rpc();
+    console.log("跨区块射击交易签名", tx);
+    const history = await program.account.gameActionHistory.fetch(gameActions);
+    assert.ok(
+      history.gameActions.some(
+        (action) =>
+          action.actionType === 1 &&
+          action.player.equals(b.publicKey) &&
+          action.target.equals(a.publicKey)
+      ),
+      "炮弹应该命中东区块中的水手A"
+    );
+  });
+
+  it("接舷战!", async () => {
+    // 两名水手在一个新的空区块中,水手A停在左上角
+    const board = await initializeChunk(0, 1);
+    const a = await spawnSailor(board);
+    await sailToEdge(a, board, 0);
+    await sailToEdge(a, board, 3);
+
+    // 水手B沿左边缘向上驶向水手A,撞上玩家的船只时必须传入船主的档案
+    const b = await spawnSailor(board);
+    await sailToEdge(b, board, 2);
+    await sailToEdge(b, board, 3);
+    let blocked = false;
+    for (let i = 0; i < 16 && !blocked; i++) {
+      try {
+        await sail(b, board, 0);
+      } catch (e) {
+        assert.include(String(e), "MissingTargetProfile");
+        blocked = true;
+      }
+    }
+    assert.ok(blocked, "水手B应该撞上了水手A");
+
+    const tx = await sail(b, board, 0, null, {
+      targetProfile: profileOf(a.publicKey),
+    });
+    console.log("接舷战交易签名", tx);
+    const sailors = [a.publicKey, b.publicKey];
+    const history = await program.account.gameActionHistory.fetch(gameActions);
+    assert.ok(
+      history.gameActions.some(
+        (action) =>
+          action.actionType === 5 &&
+          sailors.some((sailor) => sailor.equals(action.player)) &&
+          sailors.some((sailor) => sailor.equals(action.target)) &&
+          !action.player.equals(action.target)
+      ),
+      "应该记录了水手A和水手B之间的接舷战"
+    );
+  });
+
+  // 管理员设置暂停位图
+  function setPaused(paused: number) {
+    return program.methods
+      .setPaused(paused)
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+      })
+      .signers([player])
+      .rpc();
+  }
+
+  // 管理员设置射击和移动的冷却时间
+  function setCooldowns(shoot: number, move: number) {
+    return program.methods
+      .setCooldowns(new anchor.BN(shoot), new anchor.BN(move))
+      .accounts({
+        authority: player.publicKey,
+        config: gameConfig,
+        gameActions: gameActions,
+      })
+      .signers([player])
+      .rpc();
+  }
+
+  // 玩家在主棋盘上用普通炮弹射击一次
+  function shootOnce() {
+    const [level] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("level")],
+      program.programId
+    );
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    return program.methods
+      .shoot(0)
+      .accounts({
+        config: gameConfig,
+        player: player.publicKey,
+        profile: playerProfile,
+        gameDataAccount: level,
+        neighbourGameDataAccount: null,
+        chestVault: chestVault,
+        gameActions: gameActions,
+        mintRegistry: mintRegistry,
+        ammoMint: null,
+        playerAmmoAccount: null,
+        tokenProgram: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tournament: null,
+      })
+      .signers([player])
+      .rpc();
+  }
+
+  // 初始化开放海域中的一个空区块
+  async function initializeChunk(x: number, y: number) {
+    const coords = Buffer.alloc(4);
+    coords.writeUInt16LE(x, 0);
+    coords.writeUInt16LE(y, 2);
+    const [board] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chunk"), coords.subarray(0, 2), coords.subarray(2)],
+      program.programId
+    );
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    const tx = await program.methods
+      .initializeChunk(x, y, null)
+      .accounts({
+        signer: player.publicKey,
+        newGameDataAccount: board,
+        chestVault: chestVault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    console.log("初始化区块交易签名", tx);
+    return board;
+  }
+
+  // 创建一名新水手并在指定棋盘上出海
+  async function spawnSailor(board: PublicKey) {
+    const sailor = anchor.web3.Keypair.generate();
+    await transfer(sailor.publicKey, 0.5 * LAMPORTS_PER_SOL);
+    const ship = shipOf(sailor.publicKey);
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    const [shipCannonVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ship_cannons"), ship.toBuffer()],
+      program.programId
+    );
+    const [shipRumVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ship_rum"), ship.toBuffer()],
+      program.programId
+    );
+    const [tokenAccountOwnerPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("token_account_owner_pda", "utf8")],
+      program.programId
+    );
+
+    await program.methods
+      .initializeShip(0)
+      .accounts({
+        newShip: ship,
+        signer: sailor.publicKey,
+        nftAccount: sailor.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([sailor])
+      .rpc();
+    await program.methods
+      .spawnPlayer(sailor.publicKey)
+      .accounts({
+        config: gameConfig,
+        mintRegistry: mintRegistry,
+        lootTable: null,
+        player: sailor.publicKey,
+        tokenAccountOwner: sailor.publicKey,
+        gameDataAccount: board,
+        chestVault: chestVault,
+        nftAccount: sailor.publicKey,
+        ship: ship,
+        profile: profileOf(sailor.publicKey),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        shipCannonVault: shipCannonVault,
+        cannonMint: cannonTokenMint,
+        shipRumVault: shipRumVault,
+        rumMint: rumTokenMint,
+        tokenAccountOwnerPda: tokenAccountOwnerPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([sailor])
+      .rpc();
+    return sailor;
+  }
+
+  // 水手朝指定方向移动一格,传入相邻区块时可以驶入相邻区块
+  async function sail(
+    sailor: Keypair,
+    board: PublicKey,
+    direction: number,
+    neighbour: PublicKey | null = null,
+    accounts: {
+      ship?: PublicKey | null;
+      targetProfile?: PublicKey | null;
+    } = {}
+  ) {
+    const [chestVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("chestVault")],
+      program.programId
+    );
+    return program.methods
+      .movePlayerV2(direction)
+      .accounts({
+        config: gameConfig,
+        player: sailor.publicKey,
+        profile: profileOf(sailor.publicKey),
+        targetProfile: null,
+        ship: shipOf(sailor.publicKey),
+        gameDataAccount: board,
+        neighbourGameDataAccount: neighbour,
+        chestVault: chestVault,
+        gameActions: gameActions,
+        lootTable: null,
+        tournament: null,
+        ...accounts,
+      })
+      .signers([sailor])
+      .rpc();
+  }
+
+  // 水手朝指定方向一直移动到棋盘边缘
+  async function sailToEdge(
+    sailor: Keypair,
+    board: PublicKey,
+    direction: number
+  ) {
+    for (let i = 0; i < 16; i++) {
+      try {
+        await sail(sailor, board, direction);
+      } catch (e) {
+        if (!String(e).includes("WrongDirectionInput")) throw e;
+        return;
+      }
+    }
+    assert.fail("没有到达棋盘边缘");
+  }
+
+  function shipOf(owner: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("ship"), owner.toBuffer()],
+      program.programId
+    )[0];
+  }
+
+  function profileOf(owner: PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("profile"), owner.toBuffer()],
+      program.programId
+    )[0];
+  }
+
   async function transfer(toAddress: PublicKey, amount: number) {
     // 创建转账交易
     const transaction = new Transaction().add(